@@ -0,0 +1,36 @@
+// 随机策略跑训练环境，打印每秒步数：cargo run --release --example random_agent
+use std::time::Instant;
+
+use brick_breaker::gym::{Action, EnvConfig, VecEnv};
+use rand::Rng;
+
+const ENVS: usize = 16;
+const STEPS: usize = 2_000;
+
+fn main() {
+    let config = EnvConfig {
+        frame_size: Some((84, 84)),
+        ..Default::default()
+    };
+    let mut envs = VecEnv::new(ENVS, config);
+    let observations = envs.reset(42);
+    println!("特征长度 {}，画面 {} 像素", observations[0].features.len(), observations[0].frame.as_ref().map_or(0, Vec::len));
+
+    let mut rng = rand::thread_rng();
+    let mut episodes = 0;
+    let mut total_reward = 0.0;
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        let actions: Vec<Action> = (0..ENVS).map(|_| Action::from_index(rng.gen_range(0..Action::COUNT))).collect();
+        for (_, reward, done, _) in envs.step(&actions) {
+            total_reward += reward;
+            episodes += usize::from(done);
+        }
+    }
+
+    let seconds = start.elapsed().as_secs_f64();
+    println!(
+        "{} 个环境共 {} 步，用时 {:.2} 秒，每秒 {:.0} 步；结束 {} 局，总奖励 {}",
+        ENVS, ENVS * STEPS, seconds, (ENVS * STEPS) as f64 / seconds, episodes, total_reward,
+    );
+}
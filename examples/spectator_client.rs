@@ -0,0 +1,47 @@
+// 无窗口的观战客户端：连接观战服务器，逐行解析快照并打印摘要
+// 先运行 cargo run --release -- --spectate，再运行 cargo run --example spectator_client [地址] [快照数]
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+
+use brick_breaker::spectate::{Snapshot, DEFAULT_ADDRESS};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let address = args.get(1).map_or(DEFAULT_ADDRESS, String::as_str);
+    let limit: usize = args.get(2).and_then(|value| value.parse().ok()).unwrap_or(usize::MAX);
+
+    let stream = TcpStream::connect(address).unwrap_or_else(|err| {
+        eprintln!("无法连接 {}：{}", address, err);
+        std::process::exit(1);
+    });
+    println!("已连接 {}", address);
+
+    for line in BufReader::new(stream).lines().take(limit) {
+        let Ok(line) = line else { break };
+        let snapshot: Snapshot = match serde_json::from_str(&line) {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                eprintln!("无法解析快照：{}", err);
+                continue;
+            }
+        };
+        let paddles: Vec<String> = snapshot.paddles.iter().map(|paddle| format!("{:.0}", paddle.x)).collect();
+        println!(
+            "#{:<6} {:>6.1}s  分数 {:<5} 生命 {}  连击 {}  挡板 [{}]  球 {}  砖块 {}{}",
+            snapshot.frame,
+            snapshot.time,
+            snapshot.score,
+            snapshot.lives,
+            snapshot.combo,
+            paddles.join(", "),
+            snapshot.balls.len(),
+            snapshot.bricks.len(),
+            match (snapshot.game_over, snapshot.won) {
+                (true, true) => "  通关",
+                (true, false) => "  结束",
+                _ => "",
+            },
+        );
+    }
+    println!("连接已关闭");
+}
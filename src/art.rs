@@ -0,0 +1,184 @@
+// 贴图集：主题提供贴图时给砖块、球和挡板换上贴图，缺失时保持纯色矩形
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::theme::{frame_rect, AtlasDef, Theme};
+use crate::{Ball, Brick, Paddle, PADDLE_HEIGHT};
+
+pub struct ArtPlugin;
+
+impl Plugin for ArtPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpriteArt>()
+            .add_system(load_sprite_art)
+            .add_system(watch_art_load.after(load_sprite_art))
+            .add_system(apply_brick_art.after(watch_art_load))
+            .add_system(animate_ball_art.after(watch_art_load))
+            .add_system(layout_paddle_art.after(watch_art_load));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ArtStatus {
+    Flat,     // 主题没有贴图，或者贴图加载失败
+    Loading,
+    Ready,
+}
+
+#[derive(Resource)]
+pub struct SpriteArt {
+    pub status: ArtStatus,
+    image: Handle<Image>,
+    atlas: Option<AtlasDef>,
+}
+
+impl Default for SpriteArt {
+    fn default() -> Self {
+        Self {
+            status: ArtStatus::Flat,
+            image: Handle::default(),
+            atlas: None,
+        }
+    }
+}
+
+impl SpriteArt {
+    // 贴图可用时返回贴图集定义
+    fn ready(&self) -> Option<&AtlasDef> {
+        self.atlas.as_ref().filter(|_| self.status == ArtStatus::Ready)
+    }
+}
+
+// 挡板两端的端盖，side 为 -1 表示左端、1 表示右端
+#[derive(Component)]
+pub struct PaddleCap {
+    pub side: f32,
+}
+
+// 切换主题时加载新主题的贴图
+fn load_sprite_art(theme: Res<Theme>, asset_server: Res<AssetServer>, mut art: ResMut<SpriteArt>) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    *art = match &theme.def().atlas {
+        Some(atlas) => SpriteArt {
+            status: ArtStatus::Loading,
+            image: asset_server.load(atlas.image.as_str()),
+            atlas: Some(atlas.clone()),
+        },
+        None => SpriteArt::default(),
+    };
+}
+
+fn watch_art_load(asset_server: Res<AssetServer>, mut art: ResMut<SpriteArt>) {
+    if art.status != ArtStatus::Loading {
+        return;
+    }
+
+    match asset_server.get_load_state(&art.image) {
+        LoadState::Loaded => art.status = ArtStatus::Ready,
+        LoadState::Failed | LoadState::Unloaded => {
+            if let Some(atlas) = &art.atlas {
+                warn!("贴图加载失败: assets/{}，改用纯色矩形", atlas.image);
+            }
+            art.status = ArtStatus::Flat;
+        }
+        _ => {}
+    }
+}
+
+// 设置贴图和显示区域，只在确实不同的时候写入
+fn set_frame(texture: &mut Mut<Handle<Image>>, sprite: &mut Mut<Sprite>, image: &Handle<Image>, rect: Option<Rect>) {
+    if **texture != *image {
+        **texture = image.clone();
+    }
+    if sprite.rect != rect {
+        sprite.rect = rect;
+    }
+}
+
+// 砖块按损伤程度选择帧
+fn apply_brick_art(
+    art: Res<SpriteArt>,
+    mut brick_query: Query<(&Brick, &mut Sprite, &mut Handle<Image>)>,
+) {
+    let flat = Handle::default();
+
+    for (brick, mut sprite, mut texture) in brick_query.iter_mut() {
+        match art.ready().filter(|atlas| !atlas.brick_frames.is_empty()) {
+            Some(atlas) => {
+                let frames = &atlas.brick_frames;
+                let damage = 1.0 - brick.hp as f32 / brick.max_hp.max(1) as f32;
+                let index = ((damage * frames.len() as f32) as usize).min(frames.len() - 1);
+                set_frame(&mut texture, &mut sprite, &art.image, Some(frame_rect(frames[index])));
+            }
+            None => set_frame(&mut texture, &mut sprite, &flat, None),
+        }
+    }
+}
+
+// 球循环播放动画帧
+fn animate_ball_art(
+    time: Res<Time>,
+    art: Res<SpriteArt>,
+    mut ball_query: Query<(&mut Sprite, &mut Handle<Image>), With<Ball>>,
+) {
+    let flat = Handle::default();
+
+    for (mut sprite, mut texture) in ball_query.iter_mut() {
+        match art.ready().filter(|atlas| !atlas.ball_frames.is_empty()) {
+            Some(atlas) => {
+                let frames = &atlas.ball_frames;
+                let index = (time.elapsed_seconds() * atlas.ball_fps) as usize % frames.len();
+                set_frame(&mut texture, &mut sprite, &art.image, Some(frame_rect(frames[index])));
+            }
+            None => set_frame(&mut texture, &mut sprite, &flat, None),
+        }
+    }
+}
+
+// 挡板由左右端盖和中段组成，挡板变宽时只拉伸中段
+fn layout_paddle_art(
+    art: Res<SpriteArt>,
+    mut paddle_query: Query<(&Paddle, &Children, &mut Sprite, &mut Handle<Image>), Without<PaddleCap>>,
+    mut cap_query: Query<
+        (&PaddleCap, &mut Sprite, &mut Handle<Image>, &mut Transform, &mut Visibility),
+        Without<Paddle>,
+    >,
+) {
+    let flat = Handle::default();
+
+    for (paddle, children, mut sprite, mut texture) in paddle_query.iter_mut() {
+        let atlas = art.ready();
+        let cap_width = atlas.map_or(0.0, |atlas| atlas.paddle_cap.min(paddle.width / 2.0));
+        let middle_size = Some(Vec2::new(paddle.width - cap_width * 2.0, PADDLE_HEIGHT));
+        if sprite.custom_size != middle_size {
+            sprite.custom_size = middle_size;
+        }
+        match atlas {
+            Some(atlas) => set_frame(&mut texture, &mut sprite, &art.image, Some(frame_rect(atlas.paddle_middle))),
+            None => set_frame(&mut texture, &mut sprite, &flat, None),
+        }
+        let color = sprite.color;
+
+        for &child in children.iter() {
+            let Ok((cap, mut cap_sprite, mut cap_texture, mut cap_transform, mut visibility)) = cap_query.get_mut(child) else {
+                continue;
+            };
+            let Some(atlas) = atlas else {
+                if *visibility != Visibility::Hidden {
+                    *visibility = Visibility::Hidden;
+                }
+                continue;
+            };
+
+            let frame = if cap.side < 0.0 { atlas.paddle_left } else { atlas.paddle_right };
+            set_frame(&mut cap_texture, &mut cap_sprite, &art.image, Some(frame_rect(frame)));
+            cap_sprite.color = color;
+            cap_sprite.custom_size = Some(Vec2::new(cap_width, PADDLE_HEIGHT));
+            cap_transform.translation.x = cap.side * (paddle.width - cap_width) / 2.0;
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
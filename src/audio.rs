@@ -0,0 +1,284 @@
+// 音频：音效和背景音乐（见 music.rs）都在启动时用代码合成，不需要音频文件
+// 没有音频设备时 bevy 只会打印警告，播放请求被直接丢弃，游戏照常静音运行
+use std::sync::Arc;
+
+use bevy::audio::{AddAudioSource, Decodable, Source};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use rand::Rng;
+
+use crate::scoring::Scoring;
+use crate::{GameState, ImpactEvent, ImpactKind};
+
+pub const SAMPLE_RATE: u32 = 44_100;
+const VOLUME_STEP: f32 = 0.1;
+const MAX_COMBO_PITCH: u32 = 12;  // 连击音高最多升高一个八度
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<SynthSound>()
+            .init_resource::<AudioSettings>()
+            .add_event::<SfxEvent>()
+            .add_startup_system(synthesize_sounds)
+            .add_system(impacts_to_sfx)
+            .add_system(victory_sfx)
+            .add_system(play_sfx.after(impacts_to_sfx).after(victory_sfx));
+    }
+}
+
+// 合成好的单声道采样
+#[derive(TypeUuid, Clone)]
+#[uuid = "5b0c5e4a-7d0f-4f55-9b3e-2f1d6c8a9e71"]
+pub struct SynthSound {
+    pub samples: Arc<[f32]>,
+}
+
+pub struct SynthDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.position))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        Some(std::time::Duration::from_secs_f32(self.samples.len() as f32 / SAMPLE_RATE as f32))
+    }
+}
+
+impl Decodable for SynthSound {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            samples: self.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+// 音量设置，范围 0 到 1，实际音量为总音量乘以分类音量
+#[derive(Resource)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            sfx: 0.8,
+            music: 0.5,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn step(value: &mut f32, direction: f32) {
+        *value = ((*value + direction * VOLUME_STEP) * 10.0).round() / 10.0;
+        *value = value.clamp(0.0, 1.0);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Sfx {
+    Paddle,
+    Wall,
+    BrickHit,
+    BrickBreak,
+    PowerUp,
+    LifeLost,
+    Victory,
+}
+
+// 播放一个音效；砖块碎裂的音高随连击升高
+pub struct SfxEvent {
+    pub sfx: Sfx,
+    pub combo: u32,
+}
+
+impl SfxEvent {
+    pub fn new(sfx: Sfx) -> Self {
+        Self { sfx, combo: 0 }
+    }
+}
+
+#[derive(Resource)]
+struct SoundBank {
+    paddle: Handle<SynthSound>,
+    wall: Handle<SynthSound>,
+    brick_hit: Handle<SynthSound>,
+    brick_break: Handle<SynthSound>,
+    power_up: Handle<SynthSound>,
+    life_lost: Handle<SynthSound>,
+    victory: Handle<SynthSound>,
+}
+
+impl SoundBank {
+    fn get(&self, sfx: Sfx) -> &Handle<SynthSound> {
+        match sfx {
+            Sfx::Paddle => &self.paddle,
+            Sfx::Wall => &self.wall,
+            Sfx::BrickHit => &self.brick_hit,
+            Sfx::BrickBreak => &self.brick_break,
+            Sfx::PowerUp => &self.power_up,
+            Sfx::LifeLost => &self.life_lost,
+            Sfx::Victory => &self.victory,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Wave {
+    Sine,
+    Square,
+    Triangle,
+    Noise,
+}
+
+// 合成一个音：频率从 freq_start 滑到 freq_end，短起音后指数衰减
+pub fn tone(wave: Wave, freq_start: f32, freq_end: f32, duration: f32, decay: f32, volume: f32) -> Vec<f32> {
+    let mut rng = rand::thread_rng();
+    let count = (duration * SAMPLE_RATE as f32) as usize;
+    let mut phase = 0.0f32;
+
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let progress = i as f32 / count as f32;
+            let freq = freq_start + (freq_end - freq_start) * progress;
+            phase = (phase + freq / SAMPLE_RATE as f32).fract();
+
+            let value = match wave {
+                Wave::Sine => (phase * std::f32::consts::TAU).sin(),
+                Wave::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+                Wave::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+                Wave::Noise => rng.gen_range(-1.0..1.0),
+            };
+            let attack = (t / 0.005).min(1.0);
+            let release = ((duration - t) / 0.01).min(1.0);
+            value * attack * release * (-t * decay).exp() * volume
+        })
+        .collect()
+}
+
+// 把 layer 叠加到 base 的 offset 秒处
+pub fn mix_into(base: &mut Vec<f32>, layer: &[f32], offset: f32) {
+    let start = (offset * SAMPLE_RATE as f32) as usize;
+    if base.len() < start + layer.len() {
+        base.resize(start + layer.len(), 0.0);
+    }
+    for (sample, value) in base[start..].iter_mut().zip(layer) {
+        *sample += value;
+    }
+}
+
+pub fn note_freq(semitones_from_a4: i32) -> f32 {
+    440.0 * 2f32.powf(semitones_from_a4 as f32 / 12.0)
+}
+
+fn synthesize_sounds(mut commands: Commands, mut sounds: ResMut<Assets<SynthSound>>) {
+    let mut add = |samples: Vec<f32>| sounds.add(SynthSound { samples: samples.into() });
+
+    let mut victory = Vec::new();
+    for (index, semitones) in [3, 7, 10, 15].into_iter().enumerate() {
+        let freq = note_freq(semitones);
+        mix_into(&mut victory, &tone(Wave::Square, freq, freq, 0.3, 4.0, 0.2), index as f32 * 0.12);
+    }
+    let mut brick_break = tone(Wave::Square, 880.0, 660.0, 0.12, 20.0, 0.3);
+    mix_into(&mut brick_break, &tone(Wave::Noise, 0.0, 0.0, 0.08, 30.0, 0.15), 0.0);
+    let mut power_up = Vec::new();
+    for (index, semitones) in [0, 4, 7, 12].into_iter().enumerate() {
+        let freq = note_freq(semitones);
+        mix_into(&mut power_up, &tone(Wave::Triangle, freq, freq, 0.08, 10.0, 0.4), index as f32 * 0.05);
+    }
+
+    commands.insert_resource(SoundBank {
+        paddle: add(tone(Wave::Square, 440.0, 520.0, 0.08, 25.0, 0.3)),
+        wall: add(tone(Wave::Sine, 300.0, 280.0, 0.05, 40.0, 0.5)),
+        brick_hit: add(tone(Wave::Square, 620.0, 600.0, 0.06, 35.0, 0.25)),
+        brick_break: add(brick_break),
+        power_up: add(power_up),
+        life_lost: add(tone(Wave::Square, 400.0, 110.0, 0.6, 3.0, 0.3)),
+        victory: add(victory),
+    });
+}
+
+// 碰撞事件转换为音效
+fn impacts_to_sfx(
+    mut impacts: EventReader<ImpactEvent>,
+    scoring: Res<Scoring>,
+    mut sfx_events: EventWriter<SfxEvent>,
+) {
+    for impact in impacts.iter() {
+        let sfx = match impact.kind {
+            ImpactKind::Paddle => Sfx::Paddle,
+            ImpactKind::Wall => Sfx::Wall,
+            ImpactKind::BrickHit => Sfx::BrickHit,
+            ImpactKind::BrickBreak => Sfx::BrickBreak,
+            ImpactKind::LifeLost => Sfx::LifeLost,
+        };
+        sfx_events.send(SfxEvent { sfx, combo: scoring.combo() });
+    }
+}
+
+fn victory_sfx(game_state: Res<GameState>, mut played: Local<bool>, mut sfx_events: EventWriter<SfxEvent>) {
+    if !game_state.is_changed() {
+        return;
+    }
+    if game_state.won && !*played {
+        sfx_events.send(SfxEvent::new(Sfx::Victory));
+    }
+    *played = game_state.won;
+}
+
+fn play_sfx(
+    mut sfx_events: EventReader<SfxEvent>,
+    bank: Option<Res<SoundBank>>,
+    settings: Res<AudioSettings>,
+    audio: Res<Audio<SynthSound>>,
+) {
+    let Some(bank) = bank else { return };
+    let volume = settings.master * settings.sfx;
+
+    for event in sfx_events.iter() {
+        if volume <= 0.0 {
+            continue;
+        }
+        // 连击每多一次升高半音
+        let speed = if event.sfx == Sfx::BrickBreak {
+            2f32.powf(event.combo.min(MAX_COMBO_PITCH) as f32 / 12.0)
+        } else {
+            1.0
+        };
+        audio.play_with_settings(
+            bank.get(event.sfx).clone(),
+            PlaybackSettings::ONCE.with_volume(volume).with_speed(speed),
+        );
+    }
+}
@@ -0,0 +1,357 @@
+// 自动驾驶：和玩家一样通过 PlayerInput 操作挡板，预测球的落点并瞄准剩余的砖块；双人时同时操作两块挡板
+// F3 键开关；一局结束后长时间没有任何输入时进入演示模式，按任意键或手柄按钮把控制权交还玩家
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::config::GameConfig;
+use crate::coop::{Coop, MAX_PLAYERS};
+use crate::editor::LevelEditor;
+use crate::locale::Locale;
+use crate::settings::SettingsMenu;
+use crate::theme::Theme;
+use crate::{
+    read_player_input, Ball, Brick, GameState, Paddle, PlayerInput, PlayerInputSet,
+    ARENA_HEIGHT, ARENA_WIDTH, BALL_SIZE, PADDLE_HEIGHT, STICK_DEADZONE,
+};
+
+const ATTRACT_DELAY: f32 = 30.0;     // 一局结束后没有输入多少秒进入演示模式
+const RESTART_DELAY: f32 = 3.0;      // 一局结束后等待多少秒再开下一局
+const MAX_HIT_POS: f32 = 0.8;        // 瞄准时使用的挡板范围，留出边缘余量
+
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Autopilot>()
+            .add_startup_system(spawn_autopilot_text)
+            .add_system(toggle_autopilot.in_set(PlayerInputSet).before(read_player_input).before(attract_mode))
+            .add_system(attract_mode.in_set(PlayerInputSet).before(read_player_input))
+            .add_system(drive_paddle.in_set(PlayerInputSet).after(read_player_input))
+            .add_system(update_autopilot_text);
+    }
+}
+
+// 难度：重新判断局面的间隔（反应延迟）和瞄准的随机误差
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Perfect,
+}
+
+impl Difficulty {
+    fn reaction_delay(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.35,
+            Difficulty::Normal => 0.2,
+            Difficulty::Hard => 0.08,
+            Difficulty::Perfect => 0.0,
+        }
+    }
+
+    // 落点的最大偏差（像素）
+    fn aim_error(self) -> f32 {
+        match self {
+            Difficulty::Easy => 45.0,
+            Difficulty::Normal => 25.0,
+            Difficulty::Hard => 10.0,
+            Difficulty::Perfect => 0.0,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Perfect,
+            Difficulty::Perfect => Difficulty::Easy,
+        }
+    }
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "autopilot.easy",
+            Difficulty::Normal => "autopilot.normal",
+            Difficulty::Hard => "autopilot.hard",
+            Difficulty::Perfect => "autopilot.perfect",
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Autopilot {
+    pub enabled: bool,       // 玩家按 F3 打开
+    pub attract: bool,       // 演示模式，按任意键退出
+    pub difficulty: Difficulty,
+    pub restart_delay: f32,  // 一局结束后等待多少秒再开下一局
+}
+
+impl Default for Autopilot {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            attract: false,
+            difficulty: Difficulty::Normal,
+            restart_delay: RESTART_DELAY,
+        }
+    }
+}
+
+impl Autopilot {
+    // 一直开着、结束后立即重开的机器人，用于压力测试
+    pub fn bot(difficulty: Difficulty) -> Self {
+        Self {
+            enabled: true,
+            difficulty,
+            restart_delay: 0.0,
+            ..default()
+        }
+    }
+
+    pub fn active(&self) -> bool {
+        self.enabled || self.attract
+    }
+}
+
+// 机器人每帧更新的内部状态，和设置分开存放，避免每帧触发 Autopilot 的变更检测
+#[derive(Default)]
+pub struct BotPlan {
+    plan_timer: f32,                       // 距离下次重新判断局面的时间
+    targets: [Option<f32>; MAX_PLAYERS],   // 每块挡板中心要去的位置
+    game_over_time: f32,
+}
+
+#[derive(Component)]
+struct AutopilotText;
+
+// 把 x 在 [min, max] 之间来回反射，相当于球在左右墙之间弹跳
+fn fold(x: f32, min: f32, max: f32) -> f32 {
+    let width = max - min;
+    let mut offset = (x - min).rem_euclid(2.0 * width);
+    if offset > width {
+        offset = 2.0 * width - offset;
+    }
+    min + offset
+}
+
+// 预测球下一次到达 contact_y 时的 x 坐标和所需时间，考虑左右墙和顶部的反弹，忽略砖块
+pub fn predict_landing(position: Vec2, velocity: Vec2, contact_y: f32) -> Option<(f32, f32)> {
+    let top = ARENA_HEIGHT / 2.0 - BALL_SIZE / 2.0;
+    let distance = if velocity.y < 0.0 {
+        position.y - contact_y
+    } else if velocity.y > 0.0 {
+        (top - position.y).max(0.0) + (top - contact_y)
+    } else {
+        return None;
+    };
+    if distance < 0.0 {
+        return None;
+    }
+
+    let time = distance / velocity.y.abs();
+    let half_width = ARENA_WIDTH / 2.0 - BALL_SIZE / 2.0;
+    Some((fold(position.x + velocity.x * time, -half_width, half_width), time))
+}
+
+// 在挡板够得着的范围（reach 为挡板中心的移动范围）内选出最先落下的球，
+// 返回挡板中心应该去的位置，让反弹后的球飞向最近的砖块
+fn plan_target(
+    paddle: &Paddle,
+    config: &GameConfig,
+    contact_y: f32,
+    reach: (f32, f32),
+    balls: &[(Vec2, Vec2)],
+    bricks: &[Vec2],
+) -> Option<f32> {
+    let half_width = paddle.width / 2.0 + BALL_SIZE / 2.0;
+    let (landing_x, _, velocity) = balls
+        .iter()
+        .filter_map(|&(position, velocity)| {
+            predict_landing(position, velocity, contact_y).map(|(x, time)| (x, time, velocity))
+        })
+        .filter(|(x, _, _)| (reach.0 - half_width..=reach.1 + half_width).contains(x))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let target = bricks
+        .iter()
+        .filter(|brick| brick.y > contact_y)
+        .min_by(|a, b| {
+            a.distance_squared(Vec2::new(landing_x, contact_y))
+                .total_cmp(&b.distance_squared(Vec2::new(landing_x, contact_y)))
+        });
+    let Some(target) = target else {
+        return Some(landing_x);
+    };
+
+    // 反弹后竖直速度不变，水平速度由击中挡板的位置决定（见 check_collisions）
+    let rise_time = (target.y - contact_y) / velocity.y.abs().max(1.0);
+    let desired_vx = (target.x - landing_x) / rise_time;
+    let hit_pos = (desired_vx / (config.ball_speed * 0.75)).clamp(-MAX_HIT_POS, MAX_HIT_POS);
+    Some(landing_x - hit_pos * paddle.width / 2.0)
+}
+
+// 演示中的按键只用来结束演示（见 attract_mode），不再同时开关自动驾驶
+fn toggle_autopilot(keyboard: Res<Input<KeyCode>>, mut autopilot: ResMut<Autopilot>) {
+    if keyboard.just_pressed(KeyCode::F3) && !autopilot.attract {
+        autopilot.enabled = !autopilot.enabled;
+    }
+}
+
+// 一局结束后长时间没有输入时自动开始演示，不会打断进行中的一局；编辑器和设置面板打开时不计时
+// 演示中按任意键或手柄按钮重新开始一局交给玩家，鼠标移动和摇杆只用来重新计时
+fn attract_mode(
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    menu: Res<SettingsMenu>,
+    editor: Res<LevelEditor>,
+    mut idle_time: Local<f32>,
+    mut autopilot: ResMut<Autopilot>,
+    mut game_state: ResMut<GameState>,
+) {
+    let pressed = keyboard.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+        || gamepad_buttons.get_just_pressed().next().is_some();
+    let moved = mouse_motion.iter().count() > 0
+        || gamepads.iter().any(|gamepad| {
+            let stick = gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0);
+            stick.abs() > STICK_DEADZONE
+        });
+
+    if pressed && autopilot.attract {
+        autopilot.attract = false;
+        game_state.restart_requested = true;
+    }
+    if pressed || moved || autopilot.active() || menu.open || editor.active || !game_state.game_over {
+        *idle_time = 0.0;
+        return;
+    }
+
+    *idle_time += time.delta_seconds();
+    if *idle_time >= ATTRACT_DELAY {
+        autopilot.attract = true;
+        game_state.restart_requested = true;
+    }
+}
+
+pub fn drive_paddle(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    game_state: Res<GameState>,
+    autopilot: Res<Autopilot>,
+    coop: Res<Coop>,
+    mut plan: Local<BotPlan>,
+    mut input: ResMut<PlayerInput>,
+    paddle_query: Query<(&Transform, &Paddle)>,
+    ball_query: Query<(&Transform, &Ball)>,
+    brick_query: Query<(&Transform, &Brick)>,
+) {
+    if !autopilot.active() {
+        return;
+    }
+    let delta = time.delta_seconds();
+    *input = PlayerInput::default();
+
+    // 一局结束后稍等片刻再重新开始
+    if game_state.game_over {
+        plan.game_over_time += delta;
+        if plan.game_over_time >= autopilot.restart_delay {
+            plan.game_over_time = 0.0;
+            input.restart = true;
+        }
+        return;
+    }
+    plan.game_over_time = 0.0;
+
+    // 反应延迟：只在计时结束时重新判断，中间沿用上一次的判断
+    plan.plan_timer -= delta;
+    if plan.plan_timer <= 0.0 {
+        plan.plan_timer = autopilot.difficulty.reaction_delay();
+
+        let balls: Vec<(Vec2, Vec2)> = ball_query
+            .iter()
+            .map(|(transform, ball)| (transform.translation.truncate(), ball.velocity))
+            .collect();
+        // 不可击碎的砖块不作为瞄准目标；被它们从下方挡住的砖块只在没有别的目标时才瞄准，
+        // 否则球会在挡板和不可击碎的砖块之间来回竖直弹跳
+        let (steel, bricks): (Vec<_>, Vec<_>) = brick_query
+            .iter()
+            .map(|(transform, brick)| (transform.translation.truncate(), brick.indestructible))
+            .partition(|&(_, indestructible)| indestructible);
+        let bricks: Vec<Vec2> = bricks.into_iter().map(|(position, _)| position).collect();
+        let exposed: Vec<Vec2> = bricks
+            .iter()
+            .copied()
+            .filter(|brick| {
+                !steel.iter().any(|&(shield, _)| shield.y < brick.y && (shield.x - brick.x).abs() < config.brick_width)
+            })
+            .collect();
+        let bricks = if exposed.is_empty() { bricks } else { exposed };
+
+        let error = autopilot.difficulty.aim_error();
+        for (paddle_transform, paddle) in paddle_query.iter() {
+            let contact_y = paddle_transform.translation.y + PADDLE_HEIGHT / 2.0 + BALL_SIZE / 2.0;
+            let reach = coop.paddle_range(paddle.player, paddle.width);
+            plan.targets[paddle.player] = plan_target(paddle, &config, contact_y, reach, &balls, &bricks).map(|x| {
+                if error > 0.0 { x + rand::thread_rng().gen_range(-error..error) } else { x }
+            });
+        }
+    }
+
+    // 离目标很近时按比例减速，避免来回抖动
+    let step = (config.paddle_speed * delta).max(f32::EPSILON);
+    for (paddle_transform, paddle) in paddle_query.iter() {
+        if let Some(target_x) = plan.targets[paddle.player] {
+            input.direction[paddle.player] = ((target_x - paddle_transform.translation.x) / step).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+fn spawn_autopilot_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::default().with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(12.0),
+                left: Val::Percent(35.0),
+                ..default()
+            },
+            ..default()
+        }),
+        AutopilotText,
+    ));
+}
+
+fn update_autopilot_text(
+    autopilot: Res<Autopilot>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    mut text_query: Query<&mut Text, With<AutopilotText>>,
+) {
+    if !(autopilot.is_changed() || locale.is_changed() || theme.is_changed()) {
+        return;
+    }
+
+    let value = if autopilot.attract {
+        locale.get("autopilot.demo").to_string()
+    } else if autopilot.enabled {
+        let difficulty = locale.get(autopilot.difficulty.label_key());
+        locale.format("autopilot.on", &[("difficulty", &difficulty)])
+    } else {
+        String::new()
+    };
+    let style = TextStyle {
+        font: Default::default(),
+        font_size: theme.fonts().hud,
+        color: theme.colors().text_warn,
+    };
+    for mut text in text_query.iter_mut() {
+        *text = Text::from_section(value.clone(), style.clone());
+    }
+}
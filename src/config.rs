@@ -0,0 +1,250 @@
+// 玩法参数：从 assets/config/gameplay.ron 读取，文件修改后自动重新加载
+// 缺少的字段使用默认值；超出范围时报告错误，启动时退回默认配置，重新加载时保留上一次的有效配置
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::level::MAX_LEVEL_ROWS;
+use crate::{brick_cell_position, Ball, Paddle, ARENA_WIDTH, BRICK_COLS, BRICK_SPACING, PADDLE_HEIGHT, PADDLE_Y};
+
+const DEFAULT_CONFIG_PATH: &str = "assets/config/gameplay.ron";
+const CONFIG_PATH_ENV: &str = "BRICK_BREAKER_CONFIG";
+const POLL_INTERVAL: f32 = 0.5;  // 检查文件修改时间的间隔（秒）
+const MIN_PLAY_GAP: f32 = 100.0; // 最多行的关卡中最下面一行砖块和挡板之间至少留出的空间
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let source = ConfigSource::default();
+        app.insert_resource(source.load())
+            .insert_resource(source)
+            .add_system(watch_config_file)
+            .add_system(apply_config_changes.after(watch_config_file));
+    }
+}
+
+#[derive(Resource, Clone, PartialEq, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub paddle_width: f32,
+    pub paddle_speed: f32,
+    pub ball_speed: f32,
+    pub initial_lives: u32,
+    pub max_balls: usize,
+    pub brick_width: f32,
+    pub brick_height: f32,
+    pub endless_interval: f32,  // 无尽模式中砖块每隔多少秒下移一行
+    pub endless_hits: u32,      // 或者挡板每接球多少次下移一行，先到者为准
+    pub trail_interval: f32,    // 球轨迹的采样间隔（秒），越小轨迹越密
+    pub trail_length: usize,    // 球轨迹的点数
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            paddle_width: 100.0,
+            paddle_speed: 500.0,
+            ball_speed: 400.0,
+            initial_lives: 3,
+            max_balls: 5,
+            brick_width: 75.0,
+            brick_height: 20.0,
+            endless_interval: 15.0,
+            endless_hits: 6,
+            trail_interval: 0.02,
+            trail_length: 12,
+        }
+    }
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(errors: &mut Vec<String>, name: &str, value: T, range: RangeInclusive<T>) {
+    if !range.contains(&value) {
+        errors.push(format!("{} = {} 超出范围 {}..={}", name, value, range.start(), range.end()));
+    }
+}
+
+impl GameConfig {
+    // 返回所有不合法的字段，全部合法时返回空列表
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_range(&mut errors, "paddle_width", self.paddle_width, 20.0..=400.0);
+        check_range(&mut errors, "paddle_speed", self.paddle_speed, 50.0..=2000.0);
+        check_range(&mut errors, "ball_speed", self.ball_speed, 50.0..=1500.0);
+        check_range(&mut errors, "initial_lives", self.initial_lives, 1..=99);
+        check_range(&mut errors, "max_balls", self.max_balls, 1..=50);
+        check_range(&mut errors, "brick_width", self.brick_width, 10.0..=200.0);
+        check_range(&mut errors, "brick_height", self.brick_height, 5.0..=60.0);
+        check_range(&mut errors, "endless_interval", self.endless_interval, 2.0..=300.0);
+        check_range(&mut errors, "endless_hits", self.endless_hits, 1..=100);
+        check_range(&mut errors, "trail_interval", self.trail_interval, 0.005..=0.2);
+        check_range(&mut errors, "trail_length", self.trail_length, 1..=60);
+
+        // 整面砖墙必须放得进游戏区域
+        let wall_width = BRICK_COLS as f32 * (self.brick_width + BRICK_SPACING) - BRICK_SPACING;
+        if wall_width > ARENA_WIDTH {
+            errors.push(format!(
+                "brick_width = {} 时砖墙宽 {} 超过游戏区域宽度 {}",
+                self.brick_width, wall_width, ARENA_WIDTH,
+            ));
+        }
+        // 最多行的关卡也要在挡板上方留出打球的空间，否则无尽模式一开始就结束
+        let wall_bottom = brick_cell_position(self, MAX_LEVEL_ROWS - 1, 0).y - self.brick_height / 2.0;
+        let lowest = PADDLE_Y + PADDLE_HEIGHT / 2.0 + MIN_PLAY_GAP;
+        if wall_bottom < lowest {
+            errors.push(format!(
+                "brick_height = {} 时 {} 行砖墙的底部 y = {} 低于 {}（挡板上方需要留出 {}）",
+                self.brick_height, MAX_LEVEL_ROWS, wall_bottom, lowest, MIN_PLAY_GAP,
+            ));
+        }
+        errors
+    }
+}
+
+// 配置文件的位置和上次读取时的修改时间
+#[derive(Resource)]
+pub struct ConfigSource {
+    pub path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl Default for ConfigSource {
+    fn default() -> Self {
+        let path = std::env::var(CONFIG_PATH_ENV).unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+        Self {
+            modified: modified_time(path.as_ref()),
+            path: path.into(),
+        }
+    }
+}
+
+fn modified_time(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl ConfigSource {
+    // 读取配置，无效时报告错误并使用默认配置
+    pub fn load(&self) -> GameConfig {
+        self.read().unwrap_or_else(|errors| {
+            report_errors(self, &errors);
+            GameConfig::default()
+        })
+    }
+
+    // 文件不存在时使用默认配置
+    fn read(&self) -> Result<GameConfig, Vec<String>> {
+        let source = match std::fs::read_to_string(&self.path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(GameConfig::default()),
+            Err(err) => return Err(vec![err.to_string()]),
+        };
+        let config: GameConfig = ron::from_str(&source).map_err(|err| vec![err.to_string()])?;
+        let errors = config.validate();
+        if errors.is_empty() { Ok(config) } else { Err(errors) }
+    }
+}
+
+fn report_errors(source: &ConfigSource, errors: &[String]) {
+    error!("配置文件 {} 无效，未应用：", source.path.display());
+    for err in errors {
+        error!("  {}", err);
+    }
+}
+
+// 定期检查配置文件，修改后重新读取
+fn watch_config_file(
+    time: Res<Time>,
+    mut timer: Local<f32>,
+    mut source: ResMut<ConfigSource>,
+    mut config: ResMut<GameConfig>,
+) {
+    *timer += time.delta_seconds();
+    if *timer < POLL_INTERVAL {
+        return;
+    }
+    *timer = 0.0;
+
+    let modified = modified_time(&source.path);
+    if modified == source.modified {
+        return;
+    }
+    source.modified = modified;
+
+    match source.read() {
+        Ok(new_config) => {
+            if *config != new_config {
+                info!("已重新加载配置文件 {}", source.path.display());
+                *config = new_config;
+            }
+        }
+        Err(errors) => report_errors(&source, &errors),
+    }
+}
+
+// 挡板宽度和球速立即生效，轨迹间隔在下一次采样时生效；砖块尺寸、初始生命和轨迹点数在重新开始后生效
+fn apply_config_changes(
+    config: Res<GameConfig>,
+    mut last_ball_speed: Local<Option<f32>>,
+    mut paddle_query: Query<&mut Paddle>,
+    mut ball_query: Query<&mut Ball>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    for mut paddle in paddle_query.iter_mut() {
+        paddle.width = config.paddle_width;
+    }
+
+    // 场上的球按新旧基础速度的比例调整，保留加速效果
+    if let Some(previous) = last_ball_speed.replace(config.ball_speed) {
+        let ratio = config.ball_speed / previous;
+        for mut ball in ball_query.iter_mut() {
+            ball.velocity *= ratio;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_eq!(GameConfig::default().validate(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn out_of_range_fields_are_reported() {
+        let config = GameConfig { paddle_speed: 10.0, initial_lives: 0, trail_length: 100, ..default() };
+        let errors = config.validate();
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].starts_with("paddle_speed = 10"));
+        assert!(errors[1].starts_with("initial_lives = 0"));
+        assert!(errors[2].starts_with("trail_length = 100"));
+        // 范围两端都是合法值
+        let config = GameConfig { paddle_width: 20.0, max_balls: 50, endless_interval: 300.0, ..default() };
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn wall_must_fit_horizontally() {
+        // 8 列 × (95 + 5) - 5 = 795，刚好放得下
+        assert!(GameConfig { brick_width: 95.0, ..default() }.validate().is_empty());
+        let errors = GameConfig { brick_width: 120.0, ..default() }.validate();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("brick_width = 120 时砖墙宽"));
+    }
+
+    #[test]
+    fn wall_must_fit_above_paddle() {
+        assert!(GameConfig { brick_height: 30.0, ..default() }.validate().is_empty());
+        // 高度仍在 5 ~ 60 的范围内，但 10 行砖墙会压到挡板下面
+        let errors = GameConfig { brick_height: 60.0, ..default() }.validate();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("brick_height = 60 时 10 行砖墙"));
+    }
+}
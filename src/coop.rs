@@ -0,0 +1,234 @@
+// 本地双人合作：两块挡板并排或上下放置，各自染色，击碎砖块的分数记在最后击球的玩家名下
+// 玩家 1 用 A/D，玩家 2 用 ← →；手柄按连接顺序分给各玩家，单人时任何手柄都控制玩家 1
+use bevy::prelude::*;
+
+use crate::locale::Locale;
+use crate::theme::{lerp_color, Palette, Theme};
+use crate::{spawn_paddle, GameState, Paddle, PaddleGlow, ARENA_WIDTH, PADDLE_Y};
+
+pub const MAX_PLAYERS: usize = 2;
+const STACKED_GAP: f32 = 70.0;    // 上下排列时两块挡板的高度差
+const TINT_STRENGTH: f32 = 0.6;   // 玩家颜色覆盖主题挡板颜色的比例
+
+pub struct CoopPlugin;
+
+impl Plugin for CoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_coop_text)
+            .add_system(respawn_paddles)
+            .add_system(hide_out_players)
+            .add_system(update_coop_text);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CoopLayout {
+    SideBySide,  // 同一高度，各守半场
+    Stacked,     // 上下两层，都能走满全场
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Coop {
+    pub players: usize,
+    pub layout: CoopLayout,
+    pub shared_lives: bool,  // 共享生命；否则每人各有一份，用完的玩家退场
+}
+
+impl Default for Coop {
+    fn default() -> Self {
+        Self {
+            players: 1,
+            layout: CoopLayout::SideBySide,
+            shared_lives: true,
+        }
+    }
+}
+
+impl Coop {
+    pub fn two_players(layout: CoopLayout) -> Self {
+        Self { players: 2, layout, ..default() }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.players > 1
+    }
+
+    // 设置面板中依次切换：单人、双人并排、双人上下
+    pub fn next_mode(&mut self) {
+        (self.players, self.layout) = match (self.players, self.layout) {
+            (1, _) => (2, CoopLayout::SideBySide),
+            (_, CoopLayout::SideBySide) => (2, CoopLayout::Stacked),
+            _ => (1, CoopLayout::SideBySide),
+        };
+    }
+
+    pub fn mode_key(&self) -> &'static str {
+        match (self.enabled(), self.layout) {
+            (false, _) => "coop.single",
+            (true, CoopLayout::SideBySide) => "coop.side_by_side",
+            (true, CoopLayout::Stacked) => "coop.stacked",
+        }
+    }
+
+    // 挡板的初始位置
+    pub fn paddle_home(&self, player: usize) -> Vec2 {
+        match (self.enabled(), self.layout) {
+            (false, _) => Vec2::new(0.0, PADDLE_Y),
+            (true, CoopLayout::SideBySide) => {
+                let side = if player == 0 { -1.0 } else { 1.0 };
+                Vec2::new(side * ARENA_WIDTH / 4.0, PADDLE_Y)
+            }
+            (true, CoopLayout::Stacked) => Vec2::new(0.0, PADDLE_Y + player as f32 * STACKED_GAP),
+        }
+    }
+
+    // 挡板中心可以移动的 x 范围，并排时各守半场
+    pub fn paddle_range(&self, player: usize, width: f32) -> (f32, f32) {
+        let (min, max) = match (self.enabled(), self.layout) {
+            (true, CoopLayout::SideBySide) if player == 0 => (-ARENA_WIDTH / 2.0, 0.0),
+            (true, CoopLayout::SideBySide) => (0.0, ARENA_WIDTH / 2.0),
+            _ => (-ARENA_WIDTH / 2.0, ARENA_WIDTH / 2.0),
+        };
+        (min + width / 2.0, max - width / 2.0)
+    }
+
+    // 单人时保持主题颜色，双人时混入玩家颜色以便区分
+    pub fn tint(&self, colors: &Palette, player: usize, base: Color) -> Color {
+        if self.enabled() { player_tint(colors, player, base) } else { base }
+    }
+
+    // 开局时的生命：共享时只用 lives，分开时 lives 为所有玩家剩余生命之和
+    pub fn reset_lives(&self, game_state: &mut GameState, initial_lives: u32) {
+        game_state.player_lives = [0; MAX_PLAYERS];
+        if self.shared_lives {
+            game_state.lives = initial_lives;
+        } else {
+            for lives in game_state.player_lives.iter_mut().take(self.players) {
+                *lives = initial_lives;
+            }
+            game_state.lives = initial_lives * self.players as u32;
+        }
+    }
+
+    // 分开计算生命时，生命用完的玩家退场
+    pub fn is_out(&self, game_state: &GameState, player: usize) -> bool {
+        !self.shared_lives && game_state.player_lives[player] == 0
+    }
+}
+
+// 在主题颜色中混入调色板里的玩家颜色，对战模式也用它区分双方
+pub fn player_tint(colors: &Palette, player: usize, base: Color) -> Color {
+    let tint = colors.player_tints[player % colors.player_tints.len()];
+    lerp_color(base, tint, TINT_STRENGTH).with_a(base.a())
+}
+
+#[derive(Component)]
+struct CoopText;
+
+// 切换单人/双人或排列方式后重新生成挡板并开始新的一局
+fn respawn_paddles(
+    mut commands: Commands,
+    coop: Res<Coop>,
+    theme: Res<Theme>,
+    config: Res<crate::GameConfig>,
+    mut game_state: ResMut<GameState>,
+    paddle_query: Query<Entity, Or<(With<Paddle>, With<PaddleGlow>)>>,
+) {
+    if !coop.is_changed() || coop.is_added() {
+        return;
+    }
+    for entity in paddle_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for player in 0..coop.players {
+        spawn_paddle(&mut commands, player, &coop, &theme, &config);
+    }
+    game_state.restart_requested = true;
+}
+
+fn hide_out_players(
+    coop: Res<Coop>,
+    game_state: Res<GameState>,
+    mut paddle_query: Query<(&Paddle, &mut Visibility), Without<PaddleGlow>>,
+    mut glow_query: Query<(&PaddleGlow, &mut Visibility), Without<Paddle>>,
+) {
+    if !(coop.is_changed() || game_state.is_changed()) {
+        return;
+    }
+    let visibility = |player: usize| {
+        if coop.is_out(&game_state, player) { Visibility::Hidden } else { Visibility::Inherited }
+    };
+    for (paddle, mut current) in paddle_query.iter_mut() {
+        current.set_if_neq(visibility(paddle.player));
+    }
+    for (glow, mut current) in glow_query.iter_mut() {
+        current.set_if_neq(visibility(glow.player));
+    }
+}
+
+fn spawn_coop_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::default().with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(15.0),
+                left: Val::Percent(42.0),
+                ..default()
+            },
+            ..default()
+        }),
+        CoopText,
+    ));
+}
+
+// 顶部中间显示每位玩家的分数（分开计算生命时还有各自的生命），文字用玩家颜色
+fn update_coop_text(
+    coop: Res<Coop>,
+    game_state: Res<GameState>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    mut text_query: Query<&mut Text, With<CoopText>>,
+) {
+    if !(coop.is_changed() || game_state.is_changed() || locale.is_changed() || theme.is_changed()) {
+        return;
+    }
+
+    let mut sections = Vec::new();
+    if coop.enabled() {
+        for player in 0..coop.players {
+            let number = player + 1;
+            let score = game_state.player_scores[player];
+            let value = if coop.shared_lives {
+                locale.format("coop.player", &[("player", &number), ("score", &score)])
+            } else {
+                let lives = game_state.player_lives[player];
+                locale.format("coop.player_lives", &[("player", &number), ("score", &score), ("lives", &lives)])
+            };
+            let separator = if player + 1 < coop.players { "   " } else { "" };
+            sections.push(TextSection::new(
+                format!("{}{}", value, separator),
+                TextStyle {
+                    font: Default::default(),
+                    font_size: theme.fonts().hud,
+                    color: coop.tint(theme.colors(), player, theme.colors().text_primary),
+                },
+            ));
+        }
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections = sections.clone();
+    }
+}
+
+// 游戏结束时附在结算信息后的各玩家得分
+pub fn scores_line(coop: &Coop, game_state: &GameState, locale: &Locale) -> String {
+    if !coop.enabled() {
+        return String::new();
+    }
+    let scores: Vec<String> = (0..coop.players)
+        .map(|player| {
+            locale.format("coop.player", &[("player", &(player + 1)), ("score", &game_state.player_scores[player])])
+        })
+        .collect();
+    format!("\n{}", scores.join("   "))
+}
@@ -0,0 +1,438 @@
+// 关卡编辑器：F2 打开，在砖块网格上用鼠标绘制和擦除，调色板中每种砖块有自己的分值和耐久
+// Enter 用当前草稿试玩，试玩中再按 F2 回到编辑器；Ctrl+S 保存为 assets/levels 中同样格式的 RON 文件
+// 每次绘制（按下到松开算一次）、修改砖块类型或清空都可以撤销和重做
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::level::{BrickDef, Level, MAX_LEVEL_ROWS};
+use crate::locale::Locale;
+use crate::theme::Theme;
+use crate::{brick_cell_position, GameConfig, GameState, ARENA_HEIGHT, ARENA_WIDTH, BRICK_COLS, BRICK_SPACING};
+
+pub const DEFAULT_PATH: &str = "assets/levels/custom.ron";
+const MAX_UNDO: usize = 100;
+const MAX_PALETTE: usize = 16;         // 调色板最多的砖块类型，两行各 BRICK_COLS 个
+const POINTS_STEP: u32 = 10;
+const MAX_POINTS: u32 = 500;
+const MAX_HP: u32 = 9;
+const OVERLAY_Z: f32 = 50.0;           // 编辑器画面盖在暂停的游戏上面
+const PALETTE_TOP: f32 = -20.0;        // 调色板第一行的 y 坐标
+const SYMBOLS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+pub struct EditorPlugin {
+    pub path: Option<PathBuf>,  // 用 --editor 启动时直接打开编辑器，保存到这个文件
+}
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        let mut editor = LevelEditor {
+            path: self.path.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_PATH)),
+            ..default()
+        };
+        if let Some(path) = self.path.as_ref().filter(|path| path.exists()) {
+            match Level::load(path) {
+                Ok(level) => editor.draft = Some(level),
+                Err(errors) => warn!("无法读取 {}：{}", path.display(), errors.join("；")),
+            }
+        }
+        editor.active = self.path.is_some();
+
+        app.insert_resource(editor)
+            .add_startup_system(spawn_editor)
+            .add_system(toggle_editor)
+            .add_system(edit_level.after(toggle_editor))
+            .add_system(draw_editor.after(edit_level))
+            .add_system(update_editor_text.after(edit_level));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct LevelEditor {
+    pub active: bool,
+    testing: bool,              // 正在试玩草稿
+    draft: Option<Level>,       // 第一次打开时从当前关卡复制
+    path: PathBuf,
+    selected: usize,            // 调色板中选中的砖块类型（图例中的序号）
+    undo: Vec<Level>,
+    redo: Vec<Level>,
+    stroke: bool,               // 鼠标按住绘制中，整笔只记一次撤销
+    dirty: bool,                // 有没有保存的修改
+    status: String,
+}
+
+impl LevelEditor {
+    // 正在试玩草稿，最高分不记录这样的局
+    pub fn testing(&self) -> bool {
+        self.testing
+    }
+
+    // 修改草稿前调用，记录撤销点并清空重做
+    fn checkpoint(&mut self) {
+        if let Some(draft) = &self.draft {
+            self.undo.push(draft.clone());
+            if self.undo.len() > MAX_UNDO {
+                self.undo.remove(0);
+            }
+            self.redo.clear();
+            self.dirty = true;
+        }
+    }
+
+    fn undo(&mut self) {
+        if let (Some(previous), Some(draft)) = (self.undo.pop(), self.draft.as_mut()) {
+            self.redo.push(std::mem::replace(draft, previous));
+            self.dirty = true;
+        }
+    }
+
+    fn redo(&mut self) {
+        if let (Some(next), Some(draft)) = (self.redo.pop(), self.draft.as_mut()) {
+            self.undo.push(std::mem::replace(draft, next));
+            self.dirty = true;
+        }
+    }
+
+    fn selected_symbol(&self) -> Option<char> {
+        self.draft.as_ref()?.legend.keys().nth(self.selected).copied()
+    }
+}
+
+// 调色板中第 index 种砖块的位置，在网格下方排成两行
+fn palette_position(config: &GameConfig, index: usize) -> Vec2 {
+    let column = brick_cell_position(config, 0, index % BRICK_COLS);
+    Vec2::new(column.x, PALETTE_TOP - (index / BRICK_COLS) as f32 * (config.brick_height + BRICK_SPACING * 2.0))
+}
+
+// 世界坐标落在哪个格子上
+fn cell_at(config: &GameConfig, point: Vec2) -> Option<(usize, usize)> {
+    (0..MAX_LEVEL_ROWS)
+        .flat_map(|row| (0..BRICK_COLS).map(move |col| (row, col)))
+        .find(|&(row, col)| within(point, brick_cell_position(config, row, col), config))
+}
+
+fn within(point: Vec2, center: Vec2, config: &GameConfig) -> bool {
+    (point.x - center.x).abs() <= (config.brick_width + BRICK_SPACING) / 2.0
+        && (point.y - center.y).abs() <= (config.brick_height + BRICK_SPACING) / 2.0
+}
+
+// 调色板中第 index 种砖块的颜色，按序号从主题的砖块颜色中取，不可击碎的砖块用主题的钢铁色
+fn palette_color(theme: &Theme, index: usize, brick: &BrickDef) -> Color {
+    if brick.indestructible { theme.colors().steel } else { theme.brick_color(index as u32) }
+}
+
+#[derive(Component)]
+struct EditorRoot;
+
+#[derive(Component)]
+struct EditorText;
+
+fn spawn_editor(mut commands: Commands) {
+    commands.spawn((SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, OVERLAY_Z)), EditorRoot));
+    commands.spawn((
+        TextBundle::default().with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(10.0),
+                left: Val::Px(12.0),
+                ..default()
+            },
+            ..default()
+        }),
+        EditorText,
+    ));
+}
+
+// F2 打开或关闭编辑器；第一次打开时用当前关卡作为草稿
+fn toggle_editor(
+    keyboard: Res<Input<KeyCode>>,
+    level: Res<Level>,
+    mut editor: ResMut<LevelEditor>,
+) {
+    if editor.active && editor.draft.is_none() {
+        editor.draft = Some(level.clone());
+    }
+    if !keyboard.just_pressed(KeyCode::F2) {
+        return;
+    }
+    editor.active = !editor.active;
+    editor.testing = false;
+    editor.stroke = false;
+    if editor.draft.is_none() {
+        editor.draft = Some(level.clone());
+    }
+}
+
+fn edit_level(
+    keyboard: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    config: Res<GameConfig>,
+    locale: Res<Locale>,
+    mut level: ResMut<Level>,
+    mut game_state: ResMut<GameState>,
+    mut editor: ResMut<LevelEditor>,
+) {
+    if !editor.active {
+        return;
+    }
+    let ctrl = keyboard.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+    let shift = keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+
+    if ctrl && keyboard.just_pressed(KeyCode::Z) {
+        if shift { editor.redo() } else { editor.undo() }
+    }
+    if ctrl && keyboard.just_pressed(KeyCode::Y) {
+        editor.redo();
+    }
+    if ctrl && keyboard.just_pressed(KeyCode::S) {
+        let path = editor.path.clone();
+        let result = editor.draft.as_ref().map(|draft| draft.save(&path));
+        editor.status = match result {
+            Some(Ok(())) => {
+                editor.dirty = false;
+                locale.format("editor.saved", &[("path", &path.display())])
+            }
+            Some(Err(err)) => locale.format("editor.save_failed", &[("error", &err)]),
+            None => String::new(),
+        };
+    }
+
+    // 试玩：草稿替换当前关卡并重新开始
+    if keyboard.just_pressed(KeyCode::Return) {
+        let Some(draft) = editor.draft.clone() else { return };
+        let errors = draft.validate();
+        if let Some(error) = errors.first() {
+            editor.status = locale.format("editor.invalid", &[("error", error)]);
+        } else {
+            *level = draft;
+            game_state.restart_requested = true;
+            editor.active = false;
+            editor.testing = true;
+            editor.status.clear();
+        }
+        return;
+    }
+
+    // 数字键选择砖块类型，N 新增一种
+    let digits = [
+        KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5,
+        KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    ];
+    let palette_len = editor.draft.as_ref().map_or(0, |draft| draft.legend.len());
+    if let Some(index) = digits.iter().position(|&key| keyboard.just_pressed(key)) {
+        if index < palette_len {
+            editor.selected = index;
+        }
+    }
+    if keyboard.just_pressed(KeyCode::N) && palette_len < MAX_PALETTE {
+        let used = editor.draft.as_ref().map(|draft| draft.legend.clone()).unwrap_or_default();
+        if let Some(symbol) = SYMBOLS.chars().find(|symbol| !used.contains_key(symbol)) {
+            editor.checkpoint();
+            let draft = editor.draft.as_mut().unwrap();
+            draft.legend.insert(symbol, BrickDef { points: POINTS_STEP, hp: 1, indestructible: false });
+            editor.selected = draft.legend.keys().position(|&key| key == symbol).unwrap_or(0);
+        }
+    }
+
+    // [ ] 调整选中砖块的分值，- = 调整耐久，I 切换不可击碎，图例中用这个字符的砖块一起改变
+    let adjustments = [
+        (KeyCode::LBracket, -(POINTS_STEP as i64), 0, false),
+        (KeyCode::RBracket, POINTS_STEP as i64, 0, false),
+        (KeyCode::Minus, 0, -1, false),
+        (KeyCode::Equals, 0, 1, false),
+        (KeyCode::I, 0, 0, true),
+    ];
+    for (key, points, hp, toggle) in adjustments {
+        let Some(symbol) = editor.selected_symbol() else { break };
+        if !keyboard.just_pressed(key) {
+            continue;
+        }
+        let current = editor.draft.as_ref().unwrap().legend[&symbol];
+        let changed = BrickDef {
+            points: (current.points as i64 + points).clamp(POINTS_STEP as i64, MAX_POINTS as i64) as u32,
+            hp: (current.hp as i64 + hp).clamp(1, MAX_HP as i64) as u32,
+            indestructible: current.indestructible != toggle,
+        };
+        if changed != current {
+            editor.checkpoint();
+            editor.draft.as_mut().unwrap().legend.insert(symbol, changed);
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::Delete) {
+        editor.checkpoint();
+        editor.draft.as_mut().unwrap().rows.clear();
+    }
+
+    // 鼠标：左键绘制，右键擦除，按住拖动连续绘制；点击调色板选择砖块类型
+    let painting = mouse.pressed(MouseButton::Left);
+    let erasing = mouse.pressed(MouseButton::Right);
+    if !(painting || erasing) {
+        if editor.stroke {
+            editor.stroke = false;
+        }
+        return;
+    }
+    let Ok(window) = window_query.get_single() else { return };
+    let Some((camera, camera_transform)) = camera_query.iter().find(|(camera, _)| camera.is_active) else { return };
+    let Some(point) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+        .map(|ray| ray.origin.truncate())
+    else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        if let Some(index) = (0..palette_len).find(|&index| within(point, palette_position(&config, index), &config)) {
+            editor.selected = index;
+            return;
+        }
+    }
+    let Some((row, col)) = cell_at(&config, point) else { return };
+    let symbol = if painting { editor.selected_symbol() } else { None };
+    if editor.draft.as_ref().unwrap().cell(row, col) == symbol {
+        return;
+    }
+    if !editor.stroke {
+        editor.checkpoint();
+        editor.stroke = true;
+    }
+    editor.dirty = true;
+    editor.draft.as_mut().unwrap().set_cell(row, col, symbol);
+}
+
+// 网格、砖块和调色板在草稿变化时整体重画；编辑器关闭时隐藏
+fn draw_editor(
+    mut commands: Commands,
+    editor: Res<LevelEditor>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    mut root_query: Query<(Entity, &mut Visibility), With<EditorRoot>>,
+) {
+    if !(editor.is_changed() || theme.is_changed() || config.is_changed()) {
+        return;
+    }
+    let Ok((root, mut visibility)) = root_query.get_single_mut() else { return };
+    visibility.set_if_neq(if editor.active { Visibility::Inherited } else { Visibility::Hidden });
+    commands.entity(root).despawn_descendants();
+    let Some(draft) = editor.draft.as_ref().filter(|_| editor.active) else { return };
+
+    let colors = theme.colors();
+    let size = Vec2::new(config.brick_width, config.brick_height);
+    let symbols: Vec<char> = draft.legend.keys().copied().collect();
+    let color_of = |symbol: char| {
+        let index = symbols.iter().position(|&key| key == symbol).unwrap_or(0);
+        palette_color(&theme, index, &draft.legend[&symbol])
+    };
+
+    commands.entity(root).with_children(|parent| {
+        // 盖住暂停的游戏
+        parent.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: colors.background,
+                custom_size: Some(Vec2::new(ARENA_WIDTH, ARENA_HEIGHT)),
+                ..default()
+            },
+            ..default()
+        });
+
+        for row in 0..MAX_LEVEL_ROWS {
+            for col in 0..BRICK_COLS {
+                let position = brick_cell_position(&config, row, col);
+                let symbol = draft.cell(row, col);
+                let color = symbol.map_or(colors.text_info.with_a(0.12), color_of);
+                spawn_cell(parent, position, size, color, symbol, &theme);
+            }
+        }
+
+        for (index, &symbol) in symbols.iter().enumerate() {
+            let position = palette_position(&config, index);
+            // 选中的类型加一圈高亮边框
+            if index == editor.selected {
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: colors.text_good,
+                        custom_size: Some(size + Vec2::splat(BRICK_SPACING)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(0.5)),
+                    ..default()
+                });
+            }
+            spawn_cell(parent, position, size, color_of(symbol), Some(symbol), &theme);
+        }
+    });
+}
+
+fn spawn_cell(parent: &mut ChildBuilder, position: Vec2, size: Vec2, color: Color, symbol: Option<char>, theme: &Theme) {
+    parent
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(1.0)),
+            ..default()
+        })
+        .with_children(|cell| {
+            if let Some(symbol) = symbol {
+                cell.spawn(Text2dBundle {
+                    text: Text::from_section(symbol.to_string(), TextStyle {
+                        font: Default::default(),
+                        font_size: size.y * 0.8,
+                        color: theme.colors().text_primary,
+                    })
+                    .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                    ..default()
+                });
+            }
+        });
+}
+
+// 左下角：关卡名、选中的砖块类型、操作提示和保存结果；试玩时只提示如何返回
+fn update_editor_text(
+    editor: Res<LevelEditor>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    mut text_query: Query<&mut Text, With<EditorText>>,
+) {
+    if !(editor.is_changed() || locale.is_changed() || theme.is_changed()) {
+        return;
+    }
+    let mut lines = Vec::new();
+    if editor.active {
+        if let Some(draft) = &editor.draft {
+            let dirty = if editor.dirty { " *" } else { "" };
+            lines.push(locale.format("editor.title", &[("name", &draft.name), ("path", &editor.path.display()), ("dirty", &dirty)]));
+            if let Some(symbol) = editor.selected_symbol() {
+                let brick = draft.legend[&symbol];
+                lines.push(if brick.indestructible {
+                    locale.format("editor.selected_steel", &[("symbol", &symbol)])
+                } else {
+                    locale.format("editor.selected", &[("symbol", &symbol), ("points", &brick.points), ("hp", &brick.hp)])
+                });
+            }
+        }
+        lines.push(locale.get("editor.help").to_string());
+        if !editor.status.is_empty() {
+            lines.push(editor.status.clone());
+        }
+    } else if editor.testing {
+        lines.push(locale.get("editor.testing").to_string());
+    }
+
+    let style = TextStyle {
+        font: Default::default(),
+        font_size: theme.fonts().help,
+        color: theme.colors().text_info,
+    };
+    for mut text in text_query.iter_mut() {
+        *text = Text::from_section(lines.join("\n"), style.clone());
+    }
+}
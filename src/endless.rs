@@ -0,0 +1,258 @@
+// 无尽模式：每隔 endless_interval 秒，或者挡板每接球 endless_hits 次（先到者为准），所有砖块下移一行，
+// 顶部出现一行新生成的砖块；场上砖块全部击碎时立即下移。砖块到达挡板所在的高度时游戏结束
+// 得分为击碎砖块的分数加存活奖励（见 Scoring::update_survival），最高分单独记录
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::generator::{self, BrickMix, GeneratorConfig, Shape};
+use crate::locale::Locale;
+use crate::scoring::Scoring;
+use crate::sim::Simulation;
+use crate::theme::Theme;
+use crate::{
+    brick_cell_position, check_collisions, check_win_condition, gameplay_active, handle_restart, spawn_brick, Brick,
+    GameConfig, GameRng, GameState, ImpactEvent, ImpactKind, Paddle, BRICK_SPACING, PADDLE_HEIGHT,
+};
+
+pub const START_ROWS: usize = 4;  // 没有指定关卡时开局的行数
+const RAMP_DESCENTS: u32 = 20;    // 新行的难度随下移次数提高，到这么多次后不再提高
+const DENSITY_RAMP: f32 = 0.01;   // 每次下移增加的密度
+const TOUGH_RAMP: f32 = 0.3;      // 每次下移增加的坚固砖块权重
+const ARMORED_RAMP: f32 = 0.15;
+
+pub struct EndlessPlugin {
+    pub generator: Option<GeneratorConfig>,  // 用 --endless 启动时为新行的生成参数
+}
+
+impl Plugin for EndlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Endless {
+                active: self.generator.is_some(),
+                generator: self.generator.clone().unwrap_or_default(),
+                ..default()
+            })
+            .add_startup_system(spawn_endless_text)
+            .add_system(reset_endless.before(handle_restart))
+            .add_system(
+                advance_endless
+                    .after(check_collisions)
+                    .before(check_win_condition)
+                    .run_if(gameplay_active),
+            )
+            .add_system(update_endless_text.after(advance_endless));
+    }
+}
+
+// 压力测试中使用：在无窗口模拟中运行无尽模式的逻辑
+pub(crate) fn add_to_simulation(sim: &mut Simulation, generator: GeneratorConfig) {
+    sim.world.insert_resource(Endless {
+        active: true,
+        generator,
+        ..default()
+    });
+    sim.add_system(reset_endless.before(handle_restart))
+        .add_system(advance_endless.after(check_collisions).before(check_win_condition));
+}
+
+#[derive(Resource, Default)]
+pub struct Endless {
+    pub active: bool,
+    generator: GeneratorConfig,
+    since_descent: f32,  // 上次下移后经过的时间
+    hits: u32,           // 上次下移后挡板接球的次数
+    broken: u32,         // 上次下移后击碎的砖块数
+    descents: u32,
+}
+
+impl Endless {
+    // 第 descents 次下移时新行的生成参数：密度和坚固砖块的比例逐渐提高
+    fn row_generator(&self, seed: u64) -> GeneratorConfig {
+        let ramp = self.descents.min(RAMP_DESCENTS) as f32;
+        let mix = self.generator.mix;
+        GeneratorConfig {
+            seed,
+            rows: 1,
+            density: (self.generator.density + DENSITY_RAMP * ramp).min(1.0),
+            shape: Shape::Fill,
+            mix: BrickMix {
+                tough: mix.tough + TOUGH_RAMP * ramp,
+                armored: mix.armored + ARMORED_RAMP * ramp,
+                ..mix
+            },
+            difficulty: None,
+            ..self.generator.clone()
+        }
+    }
+}
+
+// 没有指定 --level 或 --generate 时开局砖墙的生成参数
+pub fn default_generator() -> GeneratorConfig {
+    GeneratorConfig {
+        seed: rand::random(),
+        rows: START_ROWS,
+        ..default()
+    }
+}
+
+#[derive(Component)]
+struct EndlessText;
+
+fn spawn_endless_text(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: Default::default(),
+                font_size: theme.fonts().help,
+                color: theme.colors().text_info,
+            },
+        )
+        .with_text_alignment(TextAlignment::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(55.0),
+                right: Val::Px(15.0),
+                ..default()
+            },
+            ..default()
+        }),
+        EndlessText,
+    ));
+}
+
+// 重新开始时清零计时和计数，开局的砖墙由 handle_restart 按关卡重新生成
+fn reset_endless(game_state: Res<GameState>, mut endless: ResMut<Endless>) {
+    if game_state.restart_requested && endless.active {
+        endless.since_descent = 0.0;
+        endless.hits = 0;
+        endless.broken = 0;
+        endless.descents = 0;
+    }
+}
+
+fn advance_endless(
+    mut commands: Commands,
+    time: Res<Time>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    mut endless: ResMut<Endless>,
+    mut game_state: ResMut<GameState>,
+    mut scoring: ResMut<Scoring>,
+    mut rng: ResMut<GameRng>,
+    mut impact_events: EventReader<ImpactEvent>,
+    mut brick_query: Query<(&mut Transform, &mut Brick)>,
+    paddle_query: Query<&Transform, (With<Paddle>, Without<Brick>)>,
+) {
+    let (mut paddle_hits, mut broken) = (0, 0);
+    for event in impact_events.iter() {
+        match event.kind {
+            ImpactKind::Paddle => paddle_hits += 1,
+            ImpactKind::BrickBreak => broken += 1,
+            _ => {}
+        }
+    }
+    if !endless.active || game_state.game_over {
+        return;
+    }
+
+    game_state.score += scoring.update_survival();
+    endless.since_descent += time.delta_seconds();
+    endless.hits += paddle_hits;
+    endless.broken += broken;
+    // 新的一行没有生成出砖块时场上也是空的，这时等计时或接球次数，不要每帧都下移
+    let cleared = endless.broken > 0 && brick_query.iter().all(|(_, brick)| brick.indestructible);
+    if cleared || endless.since_descent >= config.endless_interval || endless.hits >= config.endless_hits {
+        descend(&mut commands, &theme, &config, &mut endless, &mut scoring, &mut rng, &mut brick_query);
+    }
+
+    // 最下面的砖块碰到挡板所在的高度（双人上下排列时取较高的一块）就结束
+    let Some(paddle_line) = paddle_query.iter().map(|transform| transform.translation.y + PADDLE_HEIGHT / 2.0).reduce(f32::max) else { return };
+    let reached = brick_query
+        .iter()
+        .any(|(transform, brick)| transform.translation.y - brick.size.y / 2.0 <= paddle_line);
+    if reached {
+        game_state.game_over = true;
+        info!("砖块到达挡板，无尽模式结束：存活 {:.1} 秒，分数 {}", scoring.level_time, game_state.score);
+    }
+}
+
+// 所有砖块下移一行，顶部生成新的一行
+fn descend(
+    commands: &mut Commands,
+    theme: &Theme,
+    config: &GameConfig,
+    endless: &mut Endless,
+    scoring: &mut Scoring,
+    rng: &mut GameRng,
+    brick_query: &mut Query<(&mut Transform, &mut Brick)>,
+) {
+    let step = config.brick_height + BRICK_SPACING;
+    for (mut transform, mut brick) in brick_query.iter_mut() {
+        transform.translation.y -= step;
+        brick.row += 1;
+    }
+    scoring.shift_rows(-step);
+
+    let row_config = endless.row_generator(rng.0.gen());
+    match generator::generate(&row_config) {
+        Ok(row) => {
+            let size = Vec2::new(config.brick_width, config.brick_height);
+            for (_, col, brick) in row.bricks() {
+                spawn_brick(commands, theme, brick_cell_position(config, 0, col), size, brick, 0);
+            }
+        }
+        Err(err) => warn!("无法生成新的一行：{}", err),
+    }
+    endless.since_descent = 0.0;
+    endless.hits = 0;
+    endless.broken = 0;
+    endless.descents += 1;
+}
+
+fn update_endless_text(
+    endless: Res<Endless>,
+    config: Res<GameConfig>,
+    scoring: Res<Scoring>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    mut text_query: Query<(&mut Text, &mut Visibility), With<EndlessText>>,
+) {
+    let Ok((mut text, mut visibility)) = text_query.get_single_mut() else { return };
+    visibility.set_if_neq(if endless.active { Visibility::Inherited } else { Visibility::Hidden });
+    if !endless.active {
+        return;
+    }
+    let next = (config.endless_interval - endless.since_descent).max(0.0).ceil();
+    let hits = config.endless_hits.saturating_sub(endless.hits);
+    let value = locale.format(
+        "endless.hud",
+        &[
+            ("time", &(scoring.level_time as u32)),
+            ("bricks", &scoring.bricks_broken),
+            ("descents", &endless.descents),
+            ("next", &next),
+            ("hits", &hits),
+        ],
+    );
+    // 文字每秒才变化一次，相同时不改动，避免每帧触发文本重新布局
+    if text.sections[0].value != value || theme.is_changed() {
+        let section = &mut text.sections[0];
+        section.value = value;
+        section.style.color = theme.colors().text_info;
+        section.style.font_size = theme.fonts().help;
+    }
+}
+
+// 无尽模式的结束界面
+pub(crate) fn game_over_message(game_state: &GameState, scoring: &Scoring, locale: &Locale) -> String {
+    let breakdown = scoring.breakdown;
+    locale.format("modal.endless", &[
+        ("time", &(scoring.level_time as u32)),
+        ("bricks", &scoring.bricks_broken),
+        ("points", &(breakdown.bricks + breakdown.combo_bonus)),
+        ("rows", &breakdown.row_bonus),
+        ("survival", &breakdown.survival_bonus),
+        ("score", &game_state.score),
+    ])
+}
@@ -0,0 +1,163 @@
+// 界面字体：加载支持中文的字体，失败时明确报告并回退
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::locale::{Language, Locale};
+use crate::theme::Theme;
+
+// 默认字体路径（相对 assets 目录），主题可以指定其他字体，环境变量优先于主题
+const DEFAULT_FONT_PATH: &str = "fonts/NotoSansSC-Regular.otf";
+const FONT_PATH_ENV: &str = "BRICK_BREAKER_FONT";
+
+pub struct FontPlugin;
+
+impl Plugin for FontPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FontSettings>()
+            .add_startup_system(load_ui_font)
+            .add_system(follow_theme_font.before(watch_font_load))
+            .add_system(watch_font_load)
+            .add_system(apply_ui_font.after(watch_font_load));
+    }
+}
+
+#[derive(Resource)]
+pub struct FontSettings {
+    pub path: String,
+    pub strip_emoji: bool,  // 字体里没有表情符号，默认从文本中去掉，避免显示成方块
+}
+
+impl Default for FontSettings {
+    fn default() -> Self {
+        Self {
+            path: DEFAULT_FONT_PATH.to_string(),
+            strip_emoji: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FontStatus {
+    Loading,
+    Ready,
+    Failed,
+}
+
+#[derive(Resource)]
+pub struct UiFont {
+    pub handle: Handle<Font>,
+    pub status: FontStatus,
+}
+
+// 环境变量 > 主题字体 > 默认字体
+fn font_path(theme: &Theme) -> String {
+    std::env::var(FONT_PATH_ENV)
+        .ok()
+        .or_else(|| theme.fonts().path.clone())
+        .unwrap_or_else(|| DEFAULT_FONT_PATH.to_string())
+}
+
+fn load_ui_font(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    mut font_settings: ResMut<FontSettings>,
+) {
+    font_settings.path = font_path(&theme);
+    commands.insert_resource(UiFont {
+        handle: asset_server.load(font_settings.path.as_str()),
+        status: FontStatus::Loading,
+    });
+}
+
+// 切换主题后如果字体不同就重新加载
+fn follow_theme_font(
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    mut font_settings: ResMut<FontSettings>,
+    mut ui_font: ResMut<UiFont>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+    let path = font_path(&theme);
+    if path == font_settings.path {
+        return;
+    }
+    ui_font.handle = asset_server.load(path.as_str());
+    ui_font.status = FontStatus::Loading;
+    font_settings.path = path;
+}
+
+// 等待字体加载完成，失败时报告错误并切换到默认字体能显示的英文界面
+fn watch_font_load(
+    asset_server: Res<AssetServer>,
+    font_settings: Res<FontSettings>,
+    mut ui_font: ResMut<UiFont>,
+    mut locale: ResMut<Locale>,
+) {
+    if ui_font.status != FontStatus::Loading {
+        return;
+    }
+
+    match asset_server.get_load_state(&ui_font.handle) {
+        LoadState::Loaded => {
+            info!("界面字体已加载: {}", font_settings.path);
+            ui_font.status = FontStatus::Ready;
+        }
+        LoadState::Failed | LoadState::Unloaded => {
+            error!(
+                "界面字体加载失败: assets/{}（可通过环境变量 {} 指定其他字体）。默认字体不含中文字形，已切换为英文界面",
+                font_settings.path, FONT_PATH_ENV,
+            );
+            ui_font.status = FontStatus::Failed;
+            if locale.language.needs_cjk_font() {
+                locale.language = Language::English;
+            }
+        }
+        _ => {}
+    }
+}
+
+// 给所有文本套用界面字体，并按设置去掉表情符号
+// 先只读检查，确实需要修改时才写入，避免每帧触发文本重新排版
+fn apply_ui_font(
+    ui_font: Res<UiFont>,
+    font_settings: Res<FontSettings>,
+    mut text_query: Query<&mut Text>,
+) {
+    let font = (ui_font.status == FontStatus::Ready).then_some(&ui_font.handle);
+    let recheck_all = ui_font.is_changed();
+
+    for mut text in text_query.iter_mut() {
+        if !recheck_all && !text.is_changed() {
+            continue;
+        }
+
+        let needs_font = font.is_some_and(|font| text.sections.iter().any(|section| &section.style.font != font));
+        let needs_strip = font_settings.strip_emoji
+            && text.sections.iter().any(|section| section.value.chars().any(is_emoji));
+        if !needs_font && !needs_strip {
+            continue;
+        }
+
+        for section in text.sections.iter_mut() {
+            if let Some(font) = font {
+                section.style.font = font.clone();
+            }
+            if needs_strip {
+                section.value.retain(|c| !is_emoji(c));
+            }
+        }
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF   // 表情、图形符号、国旗等
+            | 0x2600..=0x27BF // 杂项符号和装饰符号（如 ✨）
+            | 0xFE0F          // 表情变体选择符
+            | 0x200D          // 零宽连接符
+    )
+}
@@ -0,0 +1,490 @@
+// 关卡生成器：用种子和参数生成砖块排列，同样的种子和参数总是得到同样的关卡
+// 先按形状模板（铺满、金字塔、菱形、文字）得到可以放砖块的格子，再按密度和砖块比例随机填充并保持对称
+// 每个候选布局都要通过关卡检查和从下方出发的可达性检查，给定目标难度时在多次尝试中选最接近的一个
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::level::{BrickDef, Level, MAX_LEVEL_ROWS};
+use crate::BRICK_COLS;
+
+const MAX_ATTEMPTS: usize = 200;
+const DIFFICULTY_TOLERANCE: f32 = 0.25;  // 与目标难度相差这么多以内就停止尝试
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const MAX_TEXT_LEN: usize = 2;           // 8 列只放得下两个 3 列宽的字符
+// 按默认砖块尺寸（一格 80x25）换算的球的运动限制：每上升一行最多横移的列数，球半径占的列数
+const MAX_SLOPE: f32 = 0.35;
+const BALL_RADIUS_COLS: f32 = 0.07;
+
+// 生成关卡用的砖块类型：字符、定义
+const NORMAL: (char, BrickDef) = ('o', BrickDef { points: 10, hp: 1, indestructible: false });
+const TOUGH: (char, BrickDef) = ('H', BrickDef { points: 30, hp: 2, indestructible: false });
+const ARMORED: (char, BrickDef) = ('A', BrickDef { points: 60, hp: 3, indestructible: false });
+const STEEL: (char, BrickDef) = ('#', BrickDef { points: 0, hp: 1, indestructible: true });
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+    None,
+    Mirror,      // 左右对称
+    Rotational,  // 绕中心旋转 180 度后不变
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Shape {
+    Fill,
+    Pyramid,       // 上窄下宽
+    Diamond,
+    Text(String),  // 最多两个字母或数字，3x5 点阵，笔画上的格子都放砖块，不做对称
+}
+
+// 各类砖块的相对权重，不需要加起来等于 1
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BrickMix {
+    pub normal: f32,
+    pub tough: f32,
+    pub armored: f32,
+    pub steel: f32,  // 不可击碎
+}
+
+impl Default for BrickMix {
+    fn default() -> Self {
+        Self { normal: 6.0, tough: 3.0, armored: 1.0, steel: 0.0 }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    pub rows: usize,
+    pub density: f32,             // 形状内放砖块的比例，0 到 1
+    pub symmetry: Symmetry,
+    pub shape: Shape,
+    pub mix: BrickMix,
+    pub difficulty: Option<f32>,  // 目标难度（见 estimate_difficulty），不指定时用第一个合格的布局
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            rows: 6,
+            density: 0.75,
+            symmetry: Symmetry::Mirror,
+            shape: Shape::Fill,
+            mix: BrickMix::default(),
+            difficulty: None,
+        }
+    }
+}
+
+impl GeneratorConfig {
+    // 命令行：--generate [种子] [--shape fill|pyramid|diamond|text:文字] [--symmetry none|mirror|rotational]
+    // [--rows 行数] [--density 密度] [--mix 普通,坚固,装甲,钢铁] [--difficulty 难度]；不给种子时随机
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let index = args.iter().position(|arg| arg == "--generate")?;
+        let usage = || -> ! {
+            eprintln!(" 用法：--generate [种子] [--shape fill|pyramid|diamond|text:文字] [--symmetry none|mirror|rotational]");
+            eprintln!("       [--rows 行数] [--density 0-1] [--mix 普通,坚固,装甲,钢铁] [--difficulty 难度] [--out 文件]");
+            std::process::exit(2);
+        };
+        let value = |key: &str| {
+            args.iter().position(|arg| arg == key).map(|position| args.get(position + 1).map(String::as_str).unwrap_or_else(|| usage()))
+        };
+        let number = |key: &str| value(key).map(|text| text.parse::<f32>().unwrap_or_else(|_| usage()));
+
+        let mut config = Self {
+            seed: match args.get(index + 1).filter(|arg| !arg.starts_with("--")) {
+                Some(seed) => seed.parse().unwrap_or_else(|_| usage()),
+                None => rand::random(),
+            },
+            ..Self::default()
+        };
+        if let Some(shape) = value("--shape") {
+            config.shape = match shape {
+                "fill" => Shape::Fill,
+                "pyramid" => Shape::Pyramid,
+                "diamond" => Shape::Diamond,
+                _ => match shape.strip_prefix("text:") {
+                    Some(text) => Shape::Text(text.to_string()),
+                    None => usage(),
+                },
+            };
+        }
+        if let Some(symmetry) = value("--symmetry") {
+            config.symmetry = match symmetry {
+                "none" => Symmetry::None,
+                "mirror" => Symmetry::Mirror,
+                "rotational" => Symmetry::Rotational,
+                _ => usage(),
+            };
+        }
+        if let Some(mix) = value("--mix") {
+            let weights: Vec<f32> = mix.split(',').map(|weight| weight.trim().parse().unwrap_or_else(|_| usage())).collect();
+            let [normal, tough, armored, steel] = weights[..] else { usage() };
+            config.mix = BrickMix { normal, tough, armored, steel };
+        }
+        config.rows = value("--rows").map_or(config.rows, |rows| rows.parse().unwrap_or_else(|_| usage()));
+        config.density = number("--density").unwrap_or(config.density);
+        config.difficulty = number("--difficulty");
+        // NaN 和超出范围的值直接报用法，不要等到生成时才出错
+        if !(config.density > 0.0 && config.density <= 1.0) || config.difficulty.is_some_and(|target| !(target.is_finite() && target > 0.0)) {
+            usage();
+        }
+        Some(config)
+    }
+}
+
+// 生成关卡；参数本身有误，或者多次尝试都没有合格的布局时返回原因
+pub fn generate(config: &GeneratorConfig) -> Result<Level, String> {
+    if config.rows == 0 || config.rows > MAX_LEVEL_ROWS {
+        return Err(format!("行数必须在 1 到 {} 之间", MAX_LEVEL_ROWS));
+    }
+    let mix = config.mix;
+    let weights = [mix.normal, mix.tough, mix.armored, mix.steel];
+    if weights.iter().any(|weight| weight.is_nan() || *weight < 0.0) || weights[..3].iter().sum::<f32>() <= 0.0 {
+        return Err("砖块比例不能为负，并且至少要有一种可以击碎的砖块".to_string());
+    }
+    if !config.density.is_finite() {
+        return Err("密度必须是有限的数".to_string());
+    }
+    if config.difficulty.is_some_and(|target| !(target.is_finite() && target > 0.0)) {
+        return Err("目标难度必须是正数".to_string());
+    }
+    let shape = shape_mask(&config.shape, config.rows)?;
+    let text = matches!(config.shape, Shape::Text(_));
+    let shape = if text { shape } else { symmetric_mask(&shape, config.symmetry) };
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut density = config.density.clamp(0.05, 1.0);
+    let mut strength = 1.0;  // 坚固和装甲砖块权重的倍数，调整难度时使用
+    let mut best: Option<(f32, Level)> = None;
+    for _ in 0..MAX_ATTEMPTS {
+        let level = candidate(config, &shape, density, strength, text, &mut rng);
+        if !level.validate().is_empty() || !unreachable_from_below(&level).is_empty() {
+            continue;
+        }
+        let Some(target) = config.difficulty else { return Ok(level) };
+
+        let difficulty = estimate_difficulty(&level);
+        let error = (difficulty - target).abs();
+        if best.as_ref().is_none_or(|(best_error, _)| error < *best_error) {
+            best = Some((error, level));
+        }
+        if error <= DIFFICULTY_TOLERANCE {
+            break;
+        }
+        // 先调整密度，密度到头后再调整坚固砖块的比例
+        let ratio = (target / difficulty.max(0.1)).clamp(0.5, 2.0);
+        let adjusted = (density * ratio).clamp(0.05, 1.0);
+        if text || adjusted == density {
+            strength = (strength * ratio).clamp(0.05, 20.0);
+        }
+        density = adjusted;
+    }
+    best.map(|(_, level)| level).ok_or_else(|| format!("尝试 {} 次都没有生成可以通关的布局", MAX_ATTEMPTS))
+}
+
+// 难度估计：需要的击打次数为主，不可击碎的砖块和被它们挡在上方的砖块额外加分，再按列数归一
+// 内置的经典关卡（五行各 8 块一击即碎的砖）为 5
+pub fn estimate_difficulty(level: &Level) -> f32 {
+    let steel: Vec<(usize, usize)> = level
+        .bricks()
+        .filter(|(_, _, brick)| brick.indestructible)
+        .map(|(row, col, _)| (row, col))
+        .collect();
+    let mut score = 0.5 * steel.len() as f32;
+    for (row, col, brick) in level.bricks().filter(|(_, _, brick)| !brick.indestructible) {
+        score += brick.hp as f32;
+        // 正下方有钢铁砖块时只能从侧面或上方绕过去打
+        if steel.iter().any(|&(steel_row, steel_col)| steel_col == col && steel_row > row) {
+            score += 1.5;
+        }
+    }
+    score / BRICK_COLS as f32
+}
+
+// 从下方打不到的可击碎砖块（行, 列）
+// 球从挡板斜着飞上来，斜率受挡板反弹角限制；检查每块可击碎砖块的底面是否有一条不经过不可击碎砖块的直线通到网格下方
+// 绕墙反弹、从上方落下打到的情况不算，所以比关卡检查中的包围检查严格
+pub fn unreachable_from_below(level: &Level) -> Vec<(usize, usize)> {
+    let rows = level.rows.len().min(MAX_LEVEL_ROWS);
+    let steel = |row: usize, col: i32| {
+        col >= 0
+            && (col as usize) < BRICK_COLS
+            && level.cell(row, col as usize).and_then(|symbol| level.legend.get(&symbol)).is_some_and(|brick| brick.indestructible)
+    };
+    // 从 (row, col) 的底面上 entry 处沿斜率 slope（每下降一行横移的列数）往下，经过的格子都不是不可击碎的砖块
+    let clear = |row: usize, entry: f32, slope: f32| {
+        (row + 1..rows).all(|below| {
+            let top = entry + slope * (below - row - 1) as f32;
+            let bottom = top + slope;
+            let (left, right) = (top.min(bottom) - BALL_RADIUS_COLS, top.max(bottom) + BALL_RADIUS_COLS);
+            (left.floor() as i32..=right.floor() as i32).all(|col| !steel(below, col))
+        })
+    };
+    let slopes: Vec<f32> = (-4..=4).map(|step| step as f32 / 4.0 * MAX_SLOPE).collect();
+    level
+        .bricks()
+        .filter(|&(row, col, brick)| {
+            !brick.indestructible
+                && row < rows
+                && !(1..=4).any(|part| slopes.iter().any(|&slope| clear(row, col as f32 + part as f32 / 5.0, slope)))
+        })
+        .map(|(row, col, _)| (row, col))
+        .collect()
+}
+
+// 一个候选布局：在形状内按对称方式成组填充
+fn candidate(config: &GeneratorConfig, shape: &[[bool; BRICK_COLS]], density: f32, strength: f32, text: bool, rng: &mut StdRng) -> Level {
+    let mix = config.mix;
+    let types = [
+        (NORMAL, mix.normal),
+        (TOUGH, mix.tough * strength),
+        (ARMORED, mix.armored * strength),
+        (STEEL, mix.steel),
+    ];
+    let total: f32 = types.iter().map(|(_, weight)| weight).sum();
+    let mut level = Level {
+        name: format!("生成 #{}", config.seed),
+        legend: Default::default(),
+        rows: Vec::new(),
+    };
+
+    let symmetry = if text { Symmetry::None } else { config.symmetry };
+    let rows = shape.len();
+    for (row, cells) in shape.iter().enumerate() {
+        for (col, &allowed) in cells.iter().enumerate() {
+            let group = partners(row, col, rows, symmetry);
+            // 每组只在序号最小的格子处理一次
+            if !allowed || group.iter().any(|&cell| cell < (row, col)) {
+                continue;
+            }
+            if !text && !rng.gen_bool(density as f64) {
+                continue;
+            }
+            let mut pick = rng.gen::<f32>() * total;
+            let &((symbol, brick), _) = types
+                .iter()
+                .find(|(_, weight)| {
+                    pick -= weight;
+                    pick < 0.0
+                })
+                .unwrap_or(&types[0]);
+            level.legend.insert(symbol, brick);
+            for (row, col) in group {
+                level.set_cell(row, col, Some(symbol));
+            }
+        }
+    }
+    level
+}
+
+// 和 (row, col) 一起填充的格子（包括它自己）
+fn partners(row: usize, col: usize, rows: usize, symmetry: Symmetry) -> Vec<(usize, usize)> {
+    let mut cells = vec![(row, col)];
+    let partner = match symmetry {
+        Symmetry::None => None,
+        Symmetry::Mirror => Some((row, BRICK_COLS - 1 - col)),
+        Symmetry::Rotational => Some((rows - 1 - row, BRICK_COLS - 1 - col)),
+    };
+    cells.extend(partner.filter(|&cell| cell != (row, col)));
+    cells
+}
+
+// 形状按对称方式补全，对称的一组格子只要有一个在形状内就都可以放砖块
+fn symmetric_mask(shape: &[[bool; BRICK_COLS]], symmetry: Symmetry) -> Vec<[bool; BRICK_COLS]> {
+    let rows = shape.len();
+    let mut result = shape.to_vec();
+    for (row, cells) in shape.iter().enumerate() {
+        for (col, _) in cells.iter().enumerate().filter(|(_, &allowed)| allowed) {
+            for (row, col) in partners(row, col, rows, symmetry) {
+                result[row][col] = true;
+            }
+        }
+    }
+    result
+}
+
+// 形状模板：rows 行中可以放砖块的格子
+fn shape_mask(shape: &Shape, rows: usize) -> Result<Vec<[bool; BRICK_COLS]>, String> {
+    let center = (BRICK_COLS as f32 - 1.0) / 2.0;
+    // 每行以中心为轴、半宽为 half 的一段
+    let band = |half: f32| {
+        let mut cells = [false; BRICK_COLS];
+        for (col, cell) in cells.iter_mut().enumerate() {
+            *cell = (col as f32 - center).abs() < half;
+        }
+        cells
+    };
+    let half_cols = BRICK_COLS as f32 / 2.0;
+    match shape {
+        Shape::Fill => Ok(vec![[true; BRICK_COLS]; rows]),
+        Shape::Pyramid => Ok((0..rows)
+            .map(|row| band(((row + 1) as f32 * half_cols / rows as f32).ceil()))
+            .collect()),
+        Shape::Diamond => {
+            let middle = (rows as f32 - 1.0) / 2.0;
+            let reach = (rows as f32 + 1.0) / 2.0;
+            Ok((0..rows)
+                .map(|row| band(((1.0 - (row as f32 - middle).abs() / reach) * half_cols).ceil()))
+                .collect())
+        }
+        Shape::Text(text) => text_mask(text, rows),
+    }
+}
+
+// 文字在网格中居中
+fn text_mask(text: &str, rows: usize) -> Result<Vec<[bool; BRICK_COLS]>, String> {
+    let chars: Vec<char> = text.chars().map(|symbol| symbol.to_ascii_uppercase()).collect();
+    if chars.is_empty() || chars.len() > MAX_TEXT_LEN {
+        return Err(format!("文字需要 1 到 {} 个字符", MAX_TEXT_LEN));
+    }
+    if rows < GLYPH_HEIGHT {
+        return Err(format!("文字至少需要 {} 行", GLYPH_HEIGHT));
+    }
+    let width = chars.len() * (GLYPH_WIDTH + 1) - 1;
+    let left = (BRICK_COLS - width) / 2;
+    let top = (rows - GLYPH_HEIGHT) / 2;
+    let mut mask = vec![[false; BRICK_COLS]; rows];
+    for (index, &symbol) in chars.iter().enumerate() {
+        let pattern = glyph(symbol).ok_or_else(|| format!("文字中不支持的字符 '{}'，只能使用字母和数字", symbol))?;
+        for (row, line) in pattern.iter().enumerate() {
+            for (col, pixel) in line.chars().enumerate() {
+                mask[top + row][left + index * (GLYPH_WIDTH + 1) + col] = pixel == '#';
+            }
+        }
+    }
+    Ok(mask)
+}
+
+// 3x5 点阵字体
+fn glyph(symbol: char) -> Option<[&'static str; GLYPH_HEIGHT]> {
+    Some(match symbol {
+        '0' => ["###", "#.#", "#.#", "#.#", "###"],
+        '1' => [".#.", "##.", ".#.", ".#.", "###"],
+        '2' => ["###", "..#", "###", "#..", "###"],
+        '3' => ["###", "..#", ".##", "..#", "###"],
+        '4' => ["#.#", "#.#", "###", "..#", "..#"],
+        '5' => ["###", "#..", "###", "..#", "###"],
+        '6' => ["###", "#..", "###", "#.#", "###"],
+        '7' => ["###", "..#", "..#", ".#.", ".#."],
+        '8' => ["###", "#.#", "###", "#.#", "###"],
+        '9' => ["###", "#.#", "###", "..#", "###"],
+        'A' => [".#.", "#.#", "###", "#.#", "#.#"],
+        'B' => ["##.", "#.#", "##.", "#.#", "##."],
+        'C' => [".##", "#..", "#..", "#..", ".##"],
+        'D' => ["##.", "#.#", "#.#", "#.#", "##."],
+        'E' => ["###", "#..", "##.", "#..", "###"],
+        'F' => ["###", "#..", "##.", "#..", "#.."],
+        'G' => [".##", "#..", "#.#", "#.#", ".##"],
+        'H' => ["#.#", "#.#", "###", "#.#", "#.#"],
+        'I' => ["###", ".#.", ".#.", ".#.", "###"],
+        'J' => ["..#", "..#", "..#", "#.#", ".#."],
+        'K' => ["#.#", "#.#", "##.", "#.#", "#.#"],
+        'L' => ["#..", "#..", "#..", "#..", "###"],
+        'M' => ["#.#", "###", "###", "#.#", "#.#"],
+        'N' => ["##.", "#.#", "#.#", "#.#", "#.#"],
+        'O' => [".#.", "#.#", "#.#", "#.#", ".#."],
+        'P' => ["##.", "#.#", "##.", "#..", "#.."],
+        'Q' => [".#.", "#.#", "#.#", "##.", ".##"],
+        'R' => ["##.", "#.#", "##.", "#.#", "#.#"],
+        'S' => [".##", "#..", ".#.", "..#", "##."],
+        'T' => ["###", ".#.", ".#.", ".#.", ".#."],
+        'U' => ["#.#", "#.#", "#.#", "#.#", "###"],
+        'V' => ["#.#", "#.#", "#.#", "#.#", ".#."],
+        'W' => ["#.#", "#.#", "###", "###", "#.#"],
+        'X' => ["#.#", "#.#", ".#.", "#.#", "#.#"],
+        'Y' => ["#.#", "#.#", ".#.", ".#.", ".#."],
+        'Z' => ["###", "..#", ".#.", "#..", "###"],
+        ' ' => ["...", "...", "...", "...", "..."],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(rows: &[&str]) -> Level {
+        Level {
+            name: "测试".to_string(),
+            legend: [NORMAL, STEEL].into_iter().collect(),
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+        }
+    }
+
+    fn config(seed: u64, symmetry: Symmetry) -> GeneratorConfig {
+        GeneratorConfig {
+            seed,
+            symmetry,
+            mix: BrickMix { steel: 1.0, ..BrickMix::default() },
+            ..GeneratorConfig::default()
+        }
+    }
+
+    #[test]
+    fn steel_blocks_bricks_above() {
+        assert!(unreachable_from_below(&Level::default()).is_empty());
+        // 正下方的三块钢铁砖挡住了所有斜率；最右边的砖块不受影响
+        let blocked = level(&["...o...o", "..###..."]);
+        assert_eq!(unreachable_from_below(&blocked), [(0, 3)]);
+        // 钢铁砖下面隔一行也一样挡得住，只有两端的砖块能从网格外侧斜着打到
+        let blocked = level(&["oooooooo", "........", "########"]);
+        let middle: Vec<_> = (1..BRICK_COLS - 1).map(|col| (0, col)).collect();
+        assert_eq!(unreachable_from_below(&blocked), middle);
+        // 留出缺口后可以从缺口斜着打上去
+        let open = level(&["...o....", "###.####"]);
+        assert!(unreachable_from_below(&open).is_empty());
+    }
+
+    #[test]
+    fn mirror_symmetry() {
+        for seed in 0..10 {
+            let level = generate(&config(seed, Symmetry::Mirror)).unwrap();
+            for row in &level.rows {
+                assert_eq!(row.chars().rev().collect::<String>(), *row, "种子 {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn rotational_symmetry() {
+        for seed in 0..10 {
+            let level = generate(&config(seed, Symmetry::Rotational)).unwrap();
+            let rows = level.rows.len();
+            for row in 0..rows {
+                for col in 0..BRICK_COLS {
+                    assert_eq!(level.cell(row, col), level.cell(rows - 1 - row, BRICK_COLS - 1 - col), "种子 {}", seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_level() {
+        let config = GeneratorConfig { shape: Shape::Diamond, difficulty: Some(4.0), ..config(42, Symmetry::Mirror) };
+        assert_eq!(generate(&config).unwrap(), generate(&config).unwrap());
+        let other = GeneratorConfig { seed: 43, ..config.clone() };
+        assert_ne!(generate(&config).unwrap().rows, generate(&other).unwrap().rows);
+    }
+
+    #[test]
+    fn difficulty_search_hits_target() {
+        for target in [2.0, 4.0, 6.0, 8.0] {
+            let config = GeneratorConfig { difficulty: Some(target), ..config(7, Symmetry::Mirror) };
+            let level = generate(&config).unwrap();
+            let difficulty = estimate_difficulty(&level);
+            assert!((difficulty - target).abs() <= DIFFICULTY_TOLERANCE, "目标 {} 得到 {}", target, difficulty);
+        }
+    }
+
+    #[test]
+    fn non_finite_parameters_are_rejected() {
+        for density in [f32::NAN, f32::INFINITY] {
+            assert!(generate(&GeneratorConfig { density, ..config(1, Symmetry::None) }).is_err());
+        }
+        for target in [f32::NAN, f32::INFINITY, 0.0] {
+            assert!(generate(&GeneratorConfig { difficulty: Some(target), ..config(1, Symmetry::None) }).is_err());
+        }
+    }
+}
@@ -0,0 +1,488 @@
+// 强化学习训练环境：reset(seed) 开始新的一局，step(action) 推进若干帧
+// 观测是特征向量，可以附带 CPU 光栅化的灰度画面；不开窗口，VecEnv 用多个线程并行运行多个环境
+use std::sync::mpsc;
+use std::thread;
+
+use bevy::prelude::*;
+
+use crate::level::{Level, MAX_LEVEL_ROWS};
+use crate::replay::Replay;
+use crate::sim::Simulation;
+use crate::spectate::Snapshot;
+use crate::{
+    Ball, Brick, GameConfig, GameState, Paddle, PlayerInput, ARENA_HEIGHT, ARENA_WIDTH,
+    BALL_SIZE, BRICK_COLS, BRICK_SPACING, PADDLE_HEIGHT,
+};
+
+pub const BALL_SLOTS: usize = 5;  // 观测中球的槽位数，多出的球不进入观测
+pub const BALL_FEATURES: usize = 5;
+pub const FEATURE_COUNT: usize = 1 + BALL_SLOTS * BALL_FEATURES + MAX_LEVEL_ROWS * BRICK_COLS;
+const LIFE_PENALTY: f32 = 50.0;   // 失去一条生命时扣除的奖励
+
+// 离散动作：不动、向左、向右
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Stay,
+    Left,
+    Right,
+}
+
+impl Action {
+    pub const COUNT: usize = 3;
+
+    // 超出范围的编号当作不动
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            1 => Action::Left,
+            2 => Action::Right,
+            _ => Action::Stay,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Action::Stay => 0,
+            Action::Left => 1,
+            Action::Right => 2,
+        }
+    }
+
+    fn direction(self) -> f32 {
+        match self {
+            Action::Stay => 0.0,
+            Action::Left => -1.0,
+            Action::Right => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvConfig {
+    pub game: GameConfig,
+    pub level: Level,
+    pub frame_skip: u32,                     // 每个 step 重复同一动作的帧数
+    pub max_steps: u32,                      // 超过后截断本局，0 表示不限
+    pub frame_size: Option<(usize, usize)>,  // 灰度画面的宽高，None 表示不渲染
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            game: GameConfig::default(),
+            level: Level::default(),
+            frame_skip: 4,
+            max_steps: 10_000,
+            frame_size: None,
+        }
+    }
+}
+
+// features 的排列：
+//   [0]     挡板 x，归一化到 -1..1
+//   之后    每个球槽位 5 个值：是否有球、x、y（归一化到 -1..1）、vx、vy（除以基础球速），按发球顺序排列
+//   最后    砖块网格（MAX_LEVEL_ROWS 行），按行优先排列，值为剩余耐久比例，没有砖块为 0
+// frame 为行优先的灰度像素，0 为背景
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Observation {
+    pub features: Vec<f32>,
+    pub frame: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StepInfo {
+    pub score: u32,
+    pub lives: u32,
+    pub bricks_left: usize,
+    pub won: bool,
+    pub truncated: bool,  // 因为 max_steps 结束，而不是胜负已分
+    pub steps: u32,
+}
+
+pub type StepResult = (Observation, f32, bool, StepInfo);
+
+pub struct BrickBreakerEnv {
+    config: EnvConfig,
+    sim: Simulation,
+    steps: u32,
+    replay: Replay,  // 本局的种子和动作
+}
+
+impl BrickBreakerEnv {
+    pub fn new(config: EnvConfig) -> Self {
+        let sim = Simulation::new(config.game.clone(), config.level.clone(), 0);
+        let replay = Replay { frame_skip: config.frame_skip, ..default() };
+        Self { config, sim, steps: 0, replay }
+    }
+
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.sim = Simulation::new(self.config.game.clone(), self.config.level.clone(), seed);
+        self.steps = 0;
+        self.replay = Replay {
+            seed,
+            frame_skip: self.config.frame_skip,
+            actions: Vec::new(),
+        };
+        self.observe()
+    }
+
+    // 用同样的配置和关卡 reset(replay.seed) 后依次执行其中的动作，可以重现这一局
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    // 奖励为得分增量，失去生命时扣除 LIFE_PENALTY；本局结束后继续调用只返回当前观测
+    pub fn step(&mut self, action: Action) -> StepResult {
+        let before = self.state();
+        if before.game_over {
+            return (self.observe(), 0.0, true, self.info(false));
+        }
+
+        let mut input = PlayerInput::default();
+        input.direction[0] = action.direction();
+        self.sim.world.insert_resource(input);
+        for _ in 0..self.config.frame_skip.max(1) {
+            self.sim.step();
+            if self.state().game_over {
+                break;
+            }
+        }
+        self.steps += 1;
+        self.replay.actions.push(action);
+
+        let after = self.state();
+        let lives_lost = before.lives.saturating_sub(after.lives) + u32::from(after.game_over && !after.won);
+        let reward = after.score.saturating_sub(before.score) as f32 - lives_lost as f32 * LIFE_PENALTY;
+        let truncated = !after.game_over && self.config.max_steps > 0 && self.steps >= self.config.max_steps;
+        (self.observe(), reward, after.game_over || truncated, self.info(truncated))
+    }
+
+    fn state(&self) -> GameState {
+        let state = self.sim.world.resource::<GameState>();
+        GameState {
+            score: state.score,
+            lives: state.lives,
+            game_over: state.game_over,
+            won: state.won,
+            ..default()
+        }
+    }
+
+    fn info(&mut self, truncated: bool) -> StepInfo {
+        let state = self.state();
+        let bricks_left = self
+            .sim
+            .world
+            .query::<&Brick>()
+            .iter(&self.sim.world)
+            .filter(|brick| !brick.indestructible)
+            .count();
+        StepInfo {
+            score: state.score,
+            lives: state.lives,
+            bricks_left,
+            won: state.won,
+            truncated,
+            steps: self.steps,
+        }
+    }
+
+    // 当前局面的观战快照（见 spectate.rs），帧号为本局已执行的步数
+    pub fn snapshot(&mut self) -> Snapshot {
+        Snapshot::capture(&mut self.sim.world, self.steps as u64)
+    }
+
+    pub fn observe(&mut self) -> Observation {
+        let frame = self.config.frame_size.map(|(width, height)| self.render_frame(width, height));
+        Observation {
+            features: self.features(),
+            frame,
+        }
+    }
+
+    fn features(&mut self) -> Vec<f32> {
+        let world = &mut self.sim.world;
+        let ball_speed = world.resource::<GameConfig>().ball_speed;
+        let half = Vec2::new(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0);
+        let mut features = Vec::with_capacity(FEATURE_COUNT);
+
+        let paddle_x = world
+            .query_filtered::<&Transform, With<Paddle>>()
+            .iter(world)
+            .next()
+            .map_or(0.0, |transform| transform.translation.x);
+        features.push(paddle_x / half.x);
+
+        let mut balls: Vec<(u32, Vec2, Vec2)> = world
+            .query::<(&Transform, &Ball)>()
+            .iter(world)
+            .map(|(transform, ball)| (ball.id, transform.translation.truncate(), ball.velocity))
+            .collect();
+        balls.sort_by_key(|(id, _, _)| *id);
+        for slot in 0..BALL_SLOTS {
+            match balls.get(slot) {
+                Some((_, position, velocity)) => features.extend([
+                    1.0,
+                    position.x / half.x,
+                    position.y / half.y,
+                    velocity.x / ball_speed,
+                    velocity.y / ball_speed,
+                ]),
+                None => features.extend([0.0; BALL_FEATURES]),
+            }
+        }
+
+        let mut grid = [0.0; MAX_LEVEL_ROWS * BRICK_COLS];
+        for (transform, brick) in world.query::<(&Transform, &Brick)>().iter(world) {
+            if let Some(cell) = brick_cell(transform.translation.x, brick) {
+                grid[cell] = brick.hp as f32 / brick.max_hp.max(1) as f32;
+            }
+        }
+        features.extend(grid);
+        features
+    }
+
+    // 把游戏区域缩放到 width x height 的灰度画面：砖块按耐久深浅，挡板和球最亮
+    pub fn render_frame(&mut self, width: usize, height: usize) -> Vec<u8> {
+        let world = &mut self.sim.world;
+        let mut frame = vec![0u8; width * height];
+        let scale = Vec2::new(width as f32 / ARENA_WIDTH, height as f32 / ARENA_HEIGHT);
+
+        let mut fill = |center: Vec2, size: Vec2, value: u8| {
+            // 世界坐标原点在中心、y 向上；像素坐标原点在左上角
+            let min = Vec2::new(center.x - size.x / 2.0 + ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0 - center.y - size.y / 2.0) * scale;
+            let max = min + size * scale;
+            let clip = |value: f32, limit: usize| (value.max(0.0) as usize).min(limit);
+            let (x0, x1) = (clip(min.x.floor(), width), clip(max.x.ceil(), width));
+            let (y0, y1) = (clip(min.y.floor(), height), clip(max.y.ceil(), height));
+            for y in y0..y1 {
+                frame[y * width + x0..y * width + x1].fill(value);
+            }
+        };
+
+        for (transform, brick) in world.query::<(&Transform, &Brick)>().iter(world) {
+            let value = 80 + (100 * brick.hp / brick.max_hp.max(1)) as u8;
+            fill(transform.translation.truncate(), brick.size, value);
+        }
+        for (transform, paddle) in world.query::<(&Transform, &Paddle)>().iter(world) {
+            fill(transform.translation.truncate(), Vec2::new(paddle.width, PADDLE_HEIGHT), 220);
+        }
+        for transform in world.query_filtered::<&Transform, With<Ball>>().iter(world) {
+            fill(transform.translation.truncate(), Vec2::splat(BALL_SIZE), 255);
+        }
+        frame
+    }
+}
+
+// 砖块在网格中的位置，列号由 x 坐标按 spawn_brick_wall 的排列反推
+fn brick_cell(x: f32, brick: &Brick) -> Option<usize> {
+    let pitch = brick.size.x + BRICK_SPACING;
+    let total_width = BRICK_COLS as f32 * pitch - BRICK_SPACING;
+    let col = ((x + total_width / 2.0 - brick.size.x / 2.0) / pitch).round();
+    let row = brick.row as usize;
+    (col >= 0.0 && (col as usize) < BRICK_COLS && row < MAX_LEVEL_ROWS).then(|| row * BRICK_COLS + col as usize)
+}
+
+// 多个环境一起推进，按 CPU 核数分组，每组由一个常驻的工作线程运行
+// 某个环境结束后自动用下一个种子重新开始，返回的观测是新一局的第一帧
+pub struct VecEnv {
+    workers: Vec<Worker>,
+    chunk: usize,  // 每个工作线程负责的环境数，第 i 个环境在第 i / chunk 个线程中
+    count: usize,
+    next_seed: u64,
+}
+
+enum Job {
+    Step(Vec<Action>),
+    Reset(Vec<(usize, u64)>),  // 组内编号和种子
+}
+
+enum Reply {
+    Steps(Vec<StepResult>),
+    Observations(Vec<Observation>),
+}
+
+struct Worker {
+    jobs: mpsc::Sender<Job>,
+    replies: mpsc::Receiver<Reply>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl Worker {
+    fn spawn(mut envs: Vec<BrickBreakerEnv>) -> Self {
+        let (jobs, job_receiver) = mpsc::channel();
+        let (reply_sender, replies) = mpsc::channel();
+        // VecEnv 被丢弃时发送端关闭，循环结束，线程退出
+        let handle = thread::spawn(move || {
+            for job in job_receiver {
+                let reply = match job {
+                    Job::Step(actions) => Reply::Steps(
+                        envs.iter_mut().zip(actions).map(|(env, action)| env.step(action)).collect(),
+                    ),
+                    Job::Reset(seeds) => Reply::Observations(
+                        seeds.into_iter().map(|(index, seed)| envs[index].reset(seed)).collect(),
+                    ),
+                };
+                if reply_sender.send(reply).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { jobs, replies, handle }
+    }
+
+    fn send(&self, job: Job) {
+        self.jobs.send(job).expect("环境线程崩溃");
+    }
+
+    fn receive(&self) -> Reply {
+        self.replies.recv().expect("环境线程崩溃")
+    }
+}
+
+impl VecEnv {
+    pub fn new(count: usize, config: EnvConfig) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |count| count.get());
+        let chunk = count.div_ceil(threads).max(1);
+        let mut envs: Vec<_> = (0..count).map(|_| BrickBreakerEnv::new(config.clone())).collect();
+        let mut workers = Vec::new();
+        while !envs.is_empty() {
+            let rest = envs.split_off(chunk.min(envs.len()));
+            workers.push(Worker::spawn(envs));
+            envs = rest;
+        }
+        Self { workers, chunk, count, next_seed: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // 第 i 个环境使用种子 seed + i
+    pub fn reset(&mut self, seed: u64) -> Vec<Observation> {
+        self.next_seed = seed + self.count as u64;
+        let seeds = (0..self.count).map(|index| (index, seed + index as u64)).collect();
+        self.reset_envs(seeds)
+    }
+
+    // 重开指定的环境，各线程同时进行；seeds 按环境编号从小到大给出，返回的观测顺序相同
+    fn reset_envs(&mut self, seeds: Vec<(usize, u64)>) -> Vec<Observation> {
+        let mut groups: Vec<Vec<(usize, u64)>> = vec![Vec::new(); self.workers.len()];
+        for &(index, seed) in &seeds {
+            groups[index / self.chunk].push((index % self.chunk, seed));
+        }
+        for (worker, group) in self.workers.iter().zip(&groups) {
+            if !group.is_empty() {
+                worker.send(Job::Reset(group.clone()));
+            }
+        }
+        let mut observations = Vec::with_capacity(seeds.len());
+        for (worker, group) in self.workers.iter().zip(&groups) {
+            if group.is_empty() {
+                continue;
+            }
+            let Reply::Observations(batch) = worker.receive() else { unreachable!("重开只返回观测") };
+            observations.extend(batch);
+        }
+        observations
+    }
+
+    pub fn step(&mut self, actions: &[Action]) -> Vec<StepResult> {
+        assert_eq!(actions.len(), self.count, "动作数量必须等于环境数量");
+
+        for (worker, actions) in self.workers.iter().zip(actions.chunks(self.chunk)) {
+            worker.send(Job::Step(actions.to_vec()));
+        }
+        let mut results: Vec<StepResult> = Vec::with_capacity(self.count);
+        for worker in &self.workers {
+            let Reply::Steps(batch) = worker.receive() else { unreachable!("推进只返回结果") };
+            results.extend(batch);
+        }
+
+        // 按环境顺序分配新的种子，和单线程依次重开的结果一致
+        let mut finished = Vec::new();
+        for (index, result) in results.iter().enumerate() {
+            if result.2 {
+                finished.push((index, self.next_seed));
+                self.next_seed += 1;
+            }
+        }
+        if !finished.is_empty() {
+            let observations = self.reset_envs(finished.clone());
+            for ((index, _), observation) in finished.into_iter().zip(observations) {
+                results[index].0 = observation;
+            }
+        }
+        results
+    }
+}
+
+impl Drop for VecEnv {
+    fn drop(&mut self) {
+        for worker in self.workers.drain(..) {
+            let Worker { jobs, handle, .. } = worker;
+            drop(jobs);
+            // 线程崩溃的错误已经在 send / receive 中报告过
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(step: usize) -> Action {
+        Action::from_index(step * 7 / 5 % Action::COUNT)
+    }
+
+    fn trajectory(env: &mut BrickBreakerEnv, seed: u64, steps: usize) -> Vec<StepResult> {
+        env.reset(seed);
+        (0..steps).map(|step| env.step(actions(step))).collect()
+    }
+
+    #[test]
+    fn same_seed_same_trajectory() {
+        let config = EnvConfig { frame_size: Some((42, 42)), ..default() };
+        let mut env = BrickBreakerEnv::new(config.clone());
+        let first = trajectory(&mut env, 42, 600);
+        // 同一个环境重开和新建的环境都要得到完全相同的一局
+        assert_eq!(trajectory(&mut env, 42, 600), first);
+        assert_eq!(trajectory(&mut BrickBreakerEnv::new(config), 42, 600), first);
+    }
+
+    #[test]
+    fn vec_env_matches_single_envs() {
+        let config = EnvConfig { max_steps: 50, ..default() };
+        let count = 5;
+        let mut vec_env = VecEnv::new(count, config.clone());
+        let mut envs: Vec<_> = (0..count).map(|_| BrickBreakerEnv::new(config.clone())).collect();
+
+        let observations = vec_env.reset(100);
+        for (index, env) in envs.iter_mut().enumerate() {
+            assert_eq!(env.reset(100 + index as u64), observations[index]);
+        }
+        // max_steps 很小，每个环境都会结束并按顺序用 105、106……重开
+        let mut next_seed = 100 + count as u64;
+        for step in 0..120 {
+            let step_actions: Vec<Action> = (0..count).map(|index| actions(step + index)).collect();
+            let results = vec_env.step(&step_actions);
+            for (index, env) in envs.iter_mut().enumerate() {
+                let mut expected = env.step(step_actions[index]);
+                if expected.2 {
+                    expected.0 = env.reset(next_seed);
+                    next_seed += 1;
+                }
+                assert_eq!(results[index], expected);
+            }
+        }
+        assert!(next_seed > 100 + 2 * count as u64);
+    }
+}
@@ -0,0 +1,176 @@
+// 最高分：普通关卡和无尽模式、单人和双人合作分开记录，各保留前 MAX_ENTRIES 名，保存在工作目录的 highscores.ron
+// 自动驾驶参与过的局、编辑器试玩的局和用测试键结束的局不记录；文件损坏时从空榜开始，下次记录时覆盖
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::autopilot::Autopilot;
+use crate::coop::Coop;
+use crate::editor::LevelEditor;
+use crate::endless::Endless;
+use crate::level::Level;
+use crate::locale::Locale;
+use crate::scoring::Scoring;
+use crate::GameState;
+
+pub const DEFAULT_PATH: &str = "highscores.ron";
+const MAX_ENTRIES: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub enum Category {
+    Classic,
+    Endless,
+    Coop,
+    CoopEndless,
+}
+
+impl Category {
+    fn locale_key(self) -> &'static str {
+        match self {
+            Category::Classic => "highscore.classic",
+            Category::Endless => "highscore.endless",
+            Category::Coop => "highscore.coop",
+            Category::CoopEndless => "highscore.coop_endless",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub time: f32,    // 本局用时（秒），无尽模式中即存活时间
+    pub bricks: u32,  // 击碎的砖块数
+    pub level: String,
+}
+
+#[derive(Resource, Default)]
+pub struct HighScores {
+    path: PathBuf,
+    tables: BTreeMap<Category, Vec<HighScore>>,
+    latest: Option<(Category, Option<usize>)>,  // 刚结束的一局的类别和名次，没有进榜时名次为 None
+}
+
+impl HighScores {
+    pub fn load(path: &Path) -> Self {
+        let tables = match std::fs::read_to_string(path) {
+            Ok(source) => ron::from_str(&source).unwrap_or_else(|err| {
+                warn!("最高分文件 {} 无效，从空榜开始：{}", path.display(), err);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self { path: path.to_path_buf(), tables, latest: None }
+    }
+
+    pub fn table(&self, category: Category) -> &[HighScore] {
+        self.tables.get(&category).map_or(&[], Vec::as_slice)
+    }
+
+    // 加入榜单并保存，返回名次（从 0 开始），没有进榜时返回 None；同分时先记录的排在前面
+    pub fn record(&mut self, category: Category, entry: HighScore) -> Option<usize> {
+        let table = self.tables.entry(category).or_default();
+        let rank = table.iter().position(|other| entry.score > other.score).unwrap_or(table.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+        table.insert(rank, entry);
+        table.truncate(MAX_ENTRIES);
+        let source = ron::ser::to_string_pretty(&self.tables, Default::default()).expect("最高分总能序列化");
+        if let Err(err) = std::fs::write(&self.path, source) {
+            warn!("无法保存最高分到 {}：{}", self.path.display(), err);
+        }
+        Some(rank)
+    }
+
+    // 结束界面的最后一行：新纪录、进榜名次，或者这个类别的最高分
+    fn summary(&self, category: Category, locale: &Locale) -> String {
+        let line = match self.latest {
+            Some((latest, Some(0))) if latest == category => locale.get("highscore.new_record").to_string(),
+            Some((latest, Some(rank))) if latest == category => locale.format("highscore.ranked", &[("rank", &(rank + 1))]),
+            _ => match self.table(category).first() {
+                Some(best) => locale.format("highscore.best", &[("score", &best.score)]),
+                None => return String::new(),
+            },
+        };
+        format!("\n {}", line)
+    }
+
+    // 终端中列出所有类别的榜单
+    pub fn print(&self, locale: &Locale) {
+        for category in [Category::Classic, Category::Endless, Category::Coop, Category::CoopEndless] {
+            println!(" {}", locale.get(category.locale_key()));
+            for (rank, entry) in self.table(category).iter().enumerate() {
+                println!(
+                    "   {:>2}. {:>7}  {:>6.1}s  {:>4}  {}",
+                    rank + 1, entry.score, entry.time, entry.bricks, entry.level,
+                );
+            }
+        }
+    }
+}
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HighScores::load(DEFAULT_PATH.as_ref()))
+            .add_system(record_high_score);
+    }
+}
+
+// 结束界面中的最高分一行，当前模式对应的类别
+pub(crate) fn summary_line(high_scores: &HighScores, endless: &Endless, coop: &Coop, locale: &Locale) -> String {
+    high_scores.summary(category(endless, coop), locale)
+}
+
+fn category(endless: &Endless, coop: &Coop) -> Category {
+    match (coop.enabled(), endless.active) {
+        (false, false) => Category::Classic,
+        (false, true) => Category::Endless,
+        (true, false) => Category::Coop,
+        (true, true) => Category::CoopEndless,
+    }
+}
+
+// 一局结束时记录一次，重新开始后清除标记
+fn record_high_score(
+    game_state: Res<GameState>,
+    scoring: Res<Scoring>,
+    level: Res<Level>,
+    endless: Res<Endless>,
+    coop: Res<Coop>,
+    autopilot: Res<Autopilot>,
+    editor: Res<LevelEditor>,
+    mut high_scores: ResMut<HighScores>,
+    mut recorded: Local<bool>,
+    mut assisted: Local<bool>,
+) {
+    if !game_state.game_over {
+        if *recorded {
+            *recorded = false;
+            *assisted = false;
+        }
+        *assisted |= autopilot.active() || editor.testing();
+        return;
+    }
+    if *recorded {
+        return;
+    }
+    *recorded = true;
+
+    let category = category(&endless, &coop);
+    high_scores.latest = None;
+    if *assisted || game_state.debug_ending || game_state.score == 0 {
+        return;
+    }
+    let entry = HighScore {
+        score: game_state.score,
+        time: scoring.level_time,
+        bricks: scoring.bricks_broken,
+        level: level.name.clone(),
+    };
+    let rank = high_scores.record(category, entry);
+    high_scores.latest = Some((category, rank));
+}
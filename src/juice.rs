@@ -0,0 +1,253 @@
+// 打击感反馈：震屏、顿帧、砖块和屏幕闪白、挡板挤压
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{Brick, EffectSettings, ImpactEvent, ImpactKind};
+
+const MAX_SHAKE_OFFSET: f32 = 12.0;  // 最大震屏偏移（像素）
+const SHAKE_DECAY: f32 = 2.5;        // 每秒衰减的震动量
+const FLASH_DURATION: f32 = 0.12;
+const SCREEN_FLASH_ALPHA: f32 = 0.25; // 分量为 1 的碎砖事件让全屏闪白到这个不透明度
+const SCREEN_FLASH_DECAY: f32 = 3.0;  // 每秒衰减的不透明度
+const SQUASH_DURATION: f32 = 0.2;
+const HIT_STOP_THRESHOLD: f32 = 0.6; // 事件分量达到该值才触发顿帧（经典关卡中 20 分以上的砖块）
+const HIT_STOP_SECONDS: f32 = 0.065; // 分量为 1 的事件冻结的时间（秒），与帧率无关
+
+pub struct JuicePlugin;
+
+impl Plugin for JuicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<JuiceSettings>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<HitStop>()
+            .init_resource::<ScreenFlash>()
+            .add_startup_system(spawn_screen_flash)
+            .add_system(react_to_impacts)
+            .add_system(apply_screen_shake.after(react_to_impacts))
+            .add_system(tick_hit_stop.after(react_to_impacts))
+            .add_system(update_flash.after(react_to_impacts))
+            .add_system(update_screen_flash.after(react_to_impacts))
+            .add_system(update_squash.after(react_to_impacts));
+    }
+}
+
+// 打击感强度滑块，0 表示关闭对应效果
+#[derive(Resource)]
+pub struct JuiceSettings {
+    pub shake_intensity: f32,
+    pub hit_stop_intensity: f32,
+    pub flash_intensity: f32,
+    pub squash_intensity: f32,
+}
+
+impl Default for JuiceSettings {
+    fn default() -> Self {
+        Self {
+            shake_intensity: 1.0,
+            hit_stop_intensity: 1.0,
+            flash_intensity: 1.0,
+            squash_intensity: 1.0,
+        }
+    }
+}
+
+// 震屏强度，随时间衰减
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    trauma: f32,
+}
+
+// 顿帧：剩余需要冻结的时间（秒）
+#[derive(Resource, Default)]
+pub struct HitStop {
+    remaining: f32,
+}
+
+impl HitStop {
+    pub fn active(&self) -> bool {
+        self.remaining > 0.0
+    }
+}
+
+// 全屏闪白的当前不透明度，随时间衰减
+#[derive(Resource, Default)]
+struct ScreenFlash {
+    alpha: f32,
+}
+
+#[derive(Component)]
+struct ScreenFlashOverlay;
+
+#[derive(Component)]
+pub struct Flash {
+    timer: f32,
+    base_color: Color,
+}
+
+#[derive(Component)]
+pub struct Squash {
+    timer: f32,
+    amount: f32,
+}
+
+// 把冲击事件转换为各种反馈效果
+fn react_to_impacts(
+    mut commands: Commands,
+    mut impact_events: EventReader<ImpactEvent>,
+    effect_settings: Res<EffectSettings>,
+    juice_settings: Res<JuiceSettings>,
+    mut shake: ResMut<ScreenShake>,
+    mut hit_stop: ResMut<HitStop>,
+    mut screen_flash: ResMut<ScreenFlash>,
+    mut brick_query: Query<(&Sprite, Option<&mut Flash>), With<Brick>>,
+) {
+    // 减少特效模式下关闭所有打击感效果
+    if effect_settings.reduced_effects {
+        impact_events.clear();
+        return;
+    }
+
+    for event in impact_events.iter() {
+        shake.trauma = (shake.trauma + event.weight * 0.3 * juice_settings.shake_intensity).min(1.0);
+
+        if event.weight >= HIT_STOP_THRESHOLD {
+            let seconds = event.weight * HIT_STOP_SECONDS * juice_settings.hit_stop_intensity;
+            hit_stop.remaining = hit_stop.remaining.max(seconds);
+        }
+
+        // 碎掉的砖块当帧就被移除，没有东西可以闪白，改为整个屏幕闪一下
+        if event.kind == ImpactKind::BrickBreak {
+            let alpha = event.weight * SCREEN_FLASH_ALPHA * juice_settings.flash_intensity;
+            screen_flash.alpha = screen_flash.alpha.max(alpha.min(1.0));
+        }
+
+        let Some(entity) = event.entity else {
+            continue;
+        };
+
+        match event.kind {
+            ImpactKind::BrickHit if juice_settings.flash_intensity > 0.0 => {
+                if let Ok((sprite, flash)) = brick_query.get_mut(entity) {
+                    match flash {
+                        // 正在闪白时只重置计时，保留原始颜色
+                        Some(mut flash) => flash.timer = FLASH_DURATION,
+                        None => {
+                            commands.entity(entity).insert(Flash {
+                                timer: FLASH_DURATION,
+                                base_color: sprite.color,
+                            });
+                        }
+                    }
+                }
+            }
+            ImpactKind::Paddle if juice_settings.squash_intensity > 0.0 => {
+                commands.entity(entity).insert(Squash {
+                    timer: SQUASH_DURATION,
+                    amount: 0.25 * juice_settings.squash_intensity,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+// 根据震动量随机偏移相机
+fn apply_screen_shake(
+    time: Res<Time>,
+    mut shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    shake.trauma = (shake.trauma - SHAKE_DECAY * time.delta_seconds()).max(0.0);
+
+    // 震动量平方后使用，小冲击几乎不晃动
+    let strength = shake.trauma * shake.trauma * MAX_SHAKE_OFFSET;
+    let mut rng = rand::thread_rng();
+    for mut transform in camera_query.iter_mut() {
+        if strength > 0.0 {
+            transform.translation.x = rng.gen_range(-1.0..1.0) * strength;
+            transform.translation.y = rng.gen_range(-1.0..1.0) * strength;
+        } else {
+            transform.translation.x = 0.0;
+            transform.translation.y = 0.0;
+        }
+    }
+}
+
+fn tick_hit_stop(time: Res<Time>, mut hit_stop: ResMut<HitStop>) {
+    hit_stop.remaining = (hit_stop.remaining - time.delta_seconds()).max(0.0);
+}
+
+// 覆盖整个窗口的白色层，平时完全透明；在设置面板下面，不遮挡菜单
+fn spawn_screen_flash(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..default()
+            },
+            background_color: Color::rgba(1.0, 1.0, 1.0, 0.0).into(),
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        ScreenFlashOverlay,
+    ));
+}
+
+fn update_screen_flash(
+    time: Res<Time>,
+    mut screen_flash: ResMut<ScreenFlash>,
+    mut overlay_query: Query<&mut BackgroundColor, With<ScreenFlashOverlay>>,
+) {
+    let alpha = (screen_flash.alpha - SCREEN_FLASH_DECAY * time.delta_seconds()).max(0.0);
+    if alpha != screen_flash.alpha {
+        screen_flash.alpha = alpha;
+    }
+    // 完全透明后不再写入，避免每帧触发界面重新渲染
+    for mut background in overlay_query.iter_mut() {
+        if background.0.a() != alpha {
+            background.0 = Color::rgba(1.0, 1.0, 1.0, alpha);
+        }
+    }
+}
+
+// 砖块受击闪白，结束后恢复原色
+fn update_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    juice_settings: Res<JuiceSettings>,
+    mut flash_query: Query<(Entity, &mut Sprite, &mut Flash)>,
+) {
+    for (entity, mut sprite, mut flash) in flash_query.iter_mut() {
+        flash.timer -= time.delta_seconds();
+        if flash.timer <= 0.0 {
+            sprite.color = flash.base_color;
+            commands.entity(entity).remove::<Flash>();
+            continue;
+        }
+
+        let t = (flash.timer / FLASH_DURATION * juice_settings.flash_intensity).min(1.0);
+        let [r, g, b, a] = flash.base_color.as_rgba_f32();
+        sprite.color = Color::rgba(r + (1.0 - r) * t, g + (1.0 - g) * t, b + (1.0 - b) * t, a);
+    }
+}
+
+// 挡板被击中时横向拉伸、纵向压扁，然后回弹
+fn update_squash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut squash_query: Query<(Entity, &mut Transform, &mut Squash)>,
+) {
+    for (entity, mut transform, mut squash) in squash_query.iter_mut() {
+        squash.timer -= time.delta_seconds();
+        if squash.timer <= 0.0 {
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<Squash>();
+            continue;
+        }
+
+        let t = squash.timer / SQUASH_DURATION;
+        let s = (t * std::f32::consts::PI).sin() * squash.amount;
+        transform.scale = Vec3::new(1.0 + s, 1.0 - s, 1.0);
+    }
+}
@@ -0,0 +1,191 @@
+// 关卡：RON 文件中用字符画描述砖块排列，图例给出每种字符的分值和耐久
+// 每行最多 BRICK_COLS 个字符，'.' 或空格表示空位，格式参考 assets/levels/classic.ron
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::BRICK_COLS;
+
+pub const MAX_LEVEL_ROWS: usize = 10;
+const EMPTY_CELLS: [char; 2] = ['.', ' '];
+const EMPTY: char = '.';  // 保存时空位统一写成 '.'
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct BrickDef {
+    pub points: u32,
+    pub hp: u32,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub indestructible: bool,  // 无法击碎，球只会弹开；不计入通关条件
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Level {
+    pub name: String,
+    pub legend: BTreeMap<char, BrickDef>,
+    pub rows: Vec<String>,
+}
+
+// 内置的经典关卡：五行砖块，越靠上分值越高
+impl Default for Level {
+    fn default() -> Self {
+        let legend = ['A', 'B', 'C', 'D', 'E']
+            .into_iter()
+            .zip([50, 40, 30, 20, 10])
+            .map(|(symbol, points)| (symbol, BrickDef { points, hp: 1, indestructible: false }))
+            .collect();
+        let rows = ["AAAAAAAA", "BBBBBBBB", "CCCCCCCC", "DDDDDDDD", "EEEEEEEE"];
+        Self {
+            name: "经典".to_string(),
+            legend,
+            rows: rows.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+impl Level {
+    pub fn parse(source: &str) -> Result<Self, Vec<String>> {
+        let level: Level = ron::from_str(source).map_err(|err| vec![err.to_string()])?;
+        let errors = level.validate();
+        if errors.is_empty() { Ok(level) } else { Err(errors) }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Vec<String>> {
+        let source = std::fs::read_to_string(path).map_err(|err| vec![format!("{}: {}", path.display(), err)])?;
+        Self::parse(&source)
+    }
+
+    // 写成和 assets/levels 中相同格式的 RON 文本，parse 可以原样读回
+    pub fn to_ron(&self) -> String {
+        let config = ron::ser::PrettyConfig::new().indentor("    ".to_string()).depth_limit(2);
+        let body = ron::ser::to_string_pretty(self, config).expect("关卡总能序列化");
+        format!("// {}\n{}\n", self.name, body)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_ron())
+    }
+
+    // 某一格的字符，空位或超出范围时为 None
+    pub fn cell(&self, row: usize, col: usize) -> Option<char> {
+        self.rows
+            .get(row)
+            .and_then(|line| line.chars().nth(col))
+            .filter(|symbol| !EMPTY_CELLS.contains(symbol))
+    }
+
+    // 设置某一格，None 为擦除；行补齐到 BRICK_COLS 列，末尾的空行去掉
+    pub fn set_cell(&mut self, row: usize, col: usize, symbol: Option<char>) {
+        if row >= MAX_LEVEL_ROWS || col >= BRICK_COLS {
+            return;
+        }
+        while self.rows.len() <= row {
+            self.rows.push(EMPTY.to_string().repeat(BRICK_COLS));
+        }
+        let mut cells: Vec<char> = self.rows[row].chars().collect();
+        cells.resize(BRICK_COLS, EMPTY);
+        cells[col] = symbol.unwrap_or(EMPTY);
+        self.rows[row] = cells.into_iter().map(|cell| if EMPTY_CELLS.contains(&cell) { EMPTY } else { cell }).collect();
+        while self.rows.last().is_some_and(|line| line.chars().all(|cell| EMPTY_CELLS.contains(&cell))) {
+            self.rows.pop();
+        }
+    }
+
+    // 返回所有问题，没有问题时返回空列表
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.rows.len() > MAX_LEVEL_ROWS {
+            errors.push(format!("关卡有 {} 行，最多 {} 行", self.rows.len(), MAX_LEVEL_ROWS));
+        }
+        for (index, row) in self.rows.iter().enumerate() {
+            if row.chars().count() > BRICK_COLS {
+                errors.push(format!("第 {} 行超过 {} 列: \"{}\"", index + 1, BRICK_COLS, row));
+            }
+            for symbol in row.chars().filter(|symbol| !EMPTY_CELLS.contains(symbol)) {
+                if !self.legend.contains_key(&symbol) {
+                    errors.push(format!("第 {} 行的 '{}' 不在图例中", index + 1, symbol));
+                }
+            }
+        }
+        for (symbol, brick) in self.legend.iter() {
+            if EMPTY_CELLS.contains(symbol) {
+                errors.push(format!("'{}' 表示空位，不能用在图例中", symbol));
+            }
+            if brick.hp == 0 {
+                errors.push(format!("图例 '{}' 的耐久必须至少为 1", symbol));
+            }
+        }
+        if !self.bricks().any(|(_, _, brick)| !brick.indestructible) {
+            errors.push("关卡中没有可以击碎的砖块".to_string());
+        }
+        for (row, col) in self.enclosed_bricks() {
+            errors.push(format!("第 {} 行第 {} 列的砖块被不可击碎的砖块围住，球无法到达", row + 1, col + 1));
+        }
+        errors
+    }
+
+    // 被不可击碎的砖块完全围住的可击碎砖块（行, 列）
+    // 网格四周都有球能通过的空隙，所以从网格边缘的每一格出发
+    pub fn enclosed_bricks(&self) -> Vec<(usize, usize)> {
+        let rows = self.rows.len().min(MAX_LEVEL_ROWS);
+        let edges = (0..rows)
+            .flat_map(|row| (0..BRICK_COLS).map(move |col| (row, col)))
+            .filter(|&(row, col)| row == 0 || row + 1 == rows || col == 0 || col + 1 == BRICK_COLS);
+        let reached = self.reachable(edges);
+        self.bricks()
+            // 超出范围的格子由 validate 另行报告
+            .filter(|&(row, col, brick)| row < rows && col < BRICK_COLS && !brick.indestructible && !reached[row][col])
+            .map(|(row, col, _)| (row, col))
+            .collect()
+    }
+
+    // 球从 entries 中的格子出发能到达的格子，可击碎的砖块迟早会被打通，只有不可击碎的砖块挡路
+    // 砖块间的缝隙比球小，只能上下左右移动，斜向相邻的两块不可击碎砖块之间过不去
+    fn reachable(&self, entries: impl IntoIterator<Item = (usize, usize)>) -> Vec<[bool; BRICK_COLS]> {
+        let rows = self.rows.len().min(MAX_LEVEL_ROWS);
+        let blocked = |row: usize, col: usize| {
+            self.cell(row, col)
+                .and_then(|symbol| self.legend.get(&symbol))
+                .is_some_and(|brick| brick.indestructible)
+        };
+        let mut reached = vec![[false; BRICK_COLS]; rows];
+        let mut stack: Vec<(usize, usize)> = entries.into_iter().filter(|&(row, col)| row < rows && col < BRICK_COLS).collect();
+        while let Some((row, col)) = stack.pop() {
+            if reached[row][col] || blocked(row, col) {
+                continue;
+            }
+            reached[row][col] = true;
+            if row > 0 {
+                stack.push((row - 1, col));
+            }
+            if row + 1 < rows {
+                stack.push((row + 1, col));
+            }
+            if col > 0 {
+                stack.push((row, col - 1));
+            }
+            if col + 1 < BRICK_COLS {
+                stack.push((row, col + 1));
+            }
+        }
+        reached
+    }
+
+    // 所有砖块的行、列和定义
+    pub fn bricks(&self) -> impl Iterator<Item = (usize, usize, BrickDef)> + '_ {
+        self.rows.iter().enumerate().flat_map(move |(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter_map(move |(col, symbol)| self.legend.get(&symbol).map(|brick| (row, col, *brick)))
+        })
+    }
+}
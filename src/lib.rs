@@ -0,0 +1,1746 @@
+// Bevy 的系统函数参数天然较多、查询类型较长
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
+
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use bevy::window::{PrimaryWindow, WindowMode};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+mod art;
+mod audio;
+mod autopilot;
+mod config;
+mod coop;
+mod editor;
+mod endless;
+mod fonts;
+pub mod generator;
+pub mod gym;
+mod highscores;
+mod juice;
+pub mod level;
+mod locale;
+mod music;
+mod net;
+mod particles;
+mod popups;
+#[cfg(feature = "python")]
+mod python;
+pub mod replay;
+mod scoring;
+mod settings;
+mod sim;
+mod soak;
+pub mod spectate;
+mod theme;
+mod versus;
+
+use art::{ArtPlugin, PaddleCap};
+use audio::{Sfx, SfxEvent, SoundPlugin};
+use autopilot::AutopilotPlugin;
+use config::ConfigPlugin;
+use coop::{Coop, CoopLayout, CoopPlugin, MAX_PLAYERS};
+use editor::EditorPlugin;
+use endless::{Endless, EndlessPlugin};
+pub use config::GameConfig;
+use fonts::FontPlugin;
+use highscores::{HighScorePlugin, HighScores};
+use juice::{Flash, HitStop, JuicePlugin};
+use level::{BrickDef, Level};
+use locale::{Locale, LocalePlugin};
+use music::MusicPlugin;
+use particles::{ParticleBurst, ParticleEmitter, ParticlePlugin};
+use popups::{PopupPlugin, ScorePopup, ScorePopupEvent};
+use scoring::Scoring;
+use settings::{gameplay_active, SettingsPlugin};
+use spectate::SpectatorPlugin;
+use theme::{lerp_color, Theme};
+
+// 游戏常量
+// 逻辑游戏区域大小，相机按窗口大小等比缩放，多出的部分留黑边
+const ARENA_WIDTH: f32 = 800.0;
+const ARENA_HEIGHT: f32 = 600.0;
+const PADDLE_HEIGHT: f32 = 15.0;
+const PADDLE_Y: f32 = -ARENA_HEIGHT / 2.0 + 50.0;
+const BALL_SIZE: f32 = 10.0;
+const BRICK_COLS: usize = 8;
+const BRICK_SPACING: f32 = 5.0;
+const STICK_DEADZONE: f32 = 0.2;  // 手柄摇杆的死区
+// 挡板宽度、速度、球速、生命数、球数上限和砖块尺寸见 config.rs 的 GameConfig
+
+// 组件
+#[derive(Component, Clone)]
+struct Paddle {
+    width: f32,     // 当前宽度，贴图模式下只拉伸中段
+    player: usize,  // 所属玩家，单人时为 0
+}
+
+#[derive(Component, Clone)]
+struct Ball {
+    velocity: Vec2,
+    id: u32,                     // 球的唯一标识符
+    last_player: Option<usize>,  // 最后击球的玩家，击碎砖块的分数记在他名下
+}
+
+#[derive(Component, Clone)]
+struct Brick {
+    points: u32,
+    hp: u32,      // 剩余耐久，为0时砖块被消除
+    max_hp: u32,
+    row: u32,     // 所在行，用于整行清除奖励
+    size: Vec2,   // 生成时的尺寸，修改配置后已有砖块保持原尺寸
+    indestructible: bool,  // 不可击碎，球碰到只会弹开
+}
+
+impl Brick {
+    // 颜色按所在行从主题中取，不可击碎的砖块统一用钢铁色
+    fn color(&self, theme: &Theme) -> Color {
+        if self.indestructible { theme.colors().steel } else { theme.brick_color(self.row) }
+    }
+}
+
+// 砖块的子实体装饰，随砖块一起销毁
+#[derive(Component)]
+struct BrickBorder;
+
+#[derive(Component)]
+struct BrickCrack;
+
+// 分值标记，开启后不用颜色也能分辨砖块
+#[derive(Component)]
+struct BrickGlyph;
+
+#[derive(Component)]
+struct BrickHpLabel;
+
+#[derive(Component)]
+struct ScoreText;
+
+#[derive(Component)]
+struct LivesText;
+
+#[derive(Component)]
+struct GameOverText;
+
+#[derive(Component)]
+struct HelpText;
+
+#[derive(Component)]
+struct Background;
+
+#[derive(Component)]
+struct Trail {
+    positions: VecDeque<Vec3>,  // 环形缓冲区，最新的点在末尾
+    max_length: usize,
+    sample_timer: f32,          // 距离上次采样经过的时间
+}
+
+// 轨迹点精灵，作为球的子实体，index 为 0 表示最新的点
+#[derive(Component)]
+struct TrailDot {
+    index: usize,
+}
+
+#[derive(Component)]
+struct PaddleGlow {
+    player: usize,
+}
+
+#[derive(Component)]
+struct BallGlow;
+
+#[derive(Component)]
+struct GameOverModal;
+
+#[derive(Component)]
+struct ModalText;
+
+#[derive(Component)]
+struct ModalBackground;
+
+#[derive(Resource, Clone)]
+struct GameState {
+    score: u32,
+    lives: u32,
+    game_over: bool,
+    restart_requested: bool,
+    won: bool,
+    next_ball_id: u32,  // 用于生成球的唯一ID
+    player_scores: [u32; MAX_PLAYERS],  // 每位玩家击碎砖块的得分，奖励分只计入总分
+    player_lives: [u32; MAX_PLAYERS],   // 分开计算生命时每位玩家的剩余生命
+    debug_ending: bool,                 // 用 G/W 测试键结束的局，不记入最高分
+}
+
+// 碰撞/冲击事件，供打击感等反馈系统使用
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ImpactKind {
+    Paddle,
+    Wall,
+    BrickHit,    // 砖块受损但未消除
+    BrickBreak,
+    LifeLost,
+}
+
+struct ImpactEvent {
+    kind: ImpactKind,
+    weight: f32,             // 事件分量，决定震屏和顿帧强度
+    entity: Option<Entity>,  // 被击中的实体（挡板或砖块）
+}
+
+// 本帧的玩家操作，由键盘、手柄或自动驾驶写入，游戏逻辑只读这里
+#[derive(Resource, Default)]
+struct PlayerInput {
+    direction: [f32; MAX_PLAYERS],  // 每位玩家挡板的移动方向，-1 到 1
+    add_ball: bool,
+    restart: bool,
+}
+
+// 写入 PlayerInput 的系统，都在读取它的系统之前运行
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+struct PlayerInputSet;
+
+// 影响游戏结果的随机数（发球方向），训练环境用种子保证同一种子得到同样的一局
+#[derive(Resource, Clone)]
+struct GameRng(StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+// 特效设置
+#[derive(Resource, Default)]
+struct EffectSettings {
+    reduced_effects: bool,  // 减少特效模式
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            lives: GameConfig::default().initial_lives,
+            game_over: false,
+            restart_requested: false,
+            won: false,
+            next_ball_id: 1,
+            player_scores: [0; MAX_PLAYERS],
+            player_lives: [0; MAX_PLAYERS],
+            debug_ending: false,
+        }
+    }
+}
+
+// 命令行入口，main.rs 只调用这里
+pub fn run() {
+    // CI 使用：检查语言文件是否有未翻译的键
+    if std::env::args().any(|arg| arg == "--check-locales") {
+        let complete = locale::check_locales();
+        std::process::exit(if complete { 0 } else { 1 });
+    }
+
+    // 列出最高分
+    if std::env::args().any(|arg| arg == "--highscores") {
+        HighScores::load(highscores::DEFAULT_PATH.as_ref()).print(&Locale::default());
+        return;
+    }
+
+    // 指定关卡文件，例如 --level assets/levels/classic.ron，不指定时使用内置的经典关卡
+    let args: Vec<String> = std::env::args().collect();
+    let level = match args.iter().position(|arg| arg == "--level") {
+        Some(index) => {
+            let path = args.get(index + 1).map(String::as_str).unwrap_or_default();
+            Level::load(path.as_ref()).unwrap_or_else(|errors| {
+                eprintln!(" 关卡文件 {} 无效：", path);
+                for err in errors {
+                    eprintln!("   {}", err);
+                }
+                std::process::exit(1);
+            })
+        }
+        None => Level::default(),
+    };
+
+    // 生成关卡：--generate [种子] 加生成参数（见 generator::GeneratorConfig::from_args），代替 --level
+    // 加 --out 文件时只保存生成的关卡，不启动游戏
+    let generator = generator::GeneratorConfig::from_args(&args);
+    let level = match &generator {
+        Some(config) => {
+            let generated = generator::generate(config).unwrap_or_else(|err| {
+                eprintln!(" 无法生成关卡：{}", err);
+                std::process::exit(1);
+            });
+            println!(
+                " 生成关卡：种子 {}，{} 块砖，难度 {:.1}",
+                config.seed,
+                generated.bricks().count(),
+                generator::estimate_difficulty(&generated)
+            );
+            if let Some(path) = args.iter().position(|arg| arg == "--out").and_then(|index| args.get(index + 1)) {
+                if let Err(err) = generated.save(path.as_ref()) {
+                    eprintln!(" 无法保存 {}：{}", path, err);
+                    std::process::exit(1);
+                }
+                println!(" 已保存到 {}", path);
+                return;
+            }
+            generated
+        }
+        None => level,
+    };
+
+    // 无尽模式：--endless，砖块定时下移，顶部不断出现新的一行；新行按 --generate 的参数生成，
+    // 没有指定 --level 或 --generate 时开局的砖墙也由生成器生成
+    let endless = args.iter().any(|arg| arg == "--endless").then(|| generator.clone().unwrap_or_else(endless::default_generator));
+    let level = match &endless {
+        Some(config) if generator.is_none() && !args.iter().any(|arg| arg == "--level") => {
+            generator::generate(config).expect("默认参数总能生成关卡")
+        }
+        _ => level,
+    };
+
+    // 本地双人合作，两块挡板并排；设置面板中可以切换为上下排列或分开计算生命
+    let coop = if args.iter().any(|arg| arg == "--coop") {
+        Coop::two_players(CoopLayout::SideBySide)
+    } else {
+        Coop::default()
+    };
+
+    // 压力测试：无窗口快速模拟指定的分钟数，例如 --soak 120，加 --endless 时测试无尽模式
+    if let Some(index) = args.iter().position(|arg| arg == "--soak") {
+        let minutes = args.get(index + 1).and_then(|value| value.parse().ok()).unwrap_or(60.0);
+        let passed = soak::run(minutes, level, coop, endless);
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    // 联机：--net host|join 本机地址 对方地址，主机加 --coop 时为合作，否则为对战
+    // 用 --net-test 代替 --net 时不开窗口，随机操作跑固定帧数并检查双方是否同步
+    if let Some(options) = net::NetOptions::from_args(&args) {
+        let config = config::ConfigSource::default().load();
+        if args.iter().any(|arg| arg == "--net-test") {
+            let passed = net::run_test(options, level, config);
+            std::process::exit(if passed { 0 } else { 1 });
+        }
+        net::run(options, level, config);
+        return;
+    }
+
+    // 分屏对战：双方各有一块场地，击碎砖块给对手送垃圾
+    if args.iter().any(|arg| arg == "--versus") {
+        versus::run(level, config::ConfigSource::default().load());
+        return;
+    }
+
+    // 观战服务器：--spectate [地址]，默认 127.0.0.1:7878，外部工具连接后按行收到 JSON 快照
+    let spectate = args.iter().position(|arg| arg == "--spectate").map(|index| {
+        args.get(index + 1)
+            .filter(|value| !value.starts_with("--"))
+            .cloned()
+            .unwrap_or_else(|| spectate::DEFAULT_ADDRESS.to_string())
+    });
+
+    // 关卡编辑器：--editor [文件]，启动后直接打开编辑器，保存到这个文件（默认 assets/levels/custom.ron）
+    // 不加参数时也可以在游戏中按 F2 打开
+    let editor_path = args.iter().position(|arg| arg == "--editor").map(|index| {
+        args.get(index + 1)
+            .filter(|value| !value.starts_with("--"))
+            .map_or_else(|| editor::DEFAULT_PATH.into(), Into::into)
+    });
+
+    println!(" 启动打砖块游戏...");
+    run_brick_breaker(level, coop, spectate, editor_path, endless);
+}
+
+fn run_brick_breaker(
+    level: Level,
+    coop: Coop,
+    spectate: Option<String>,
+    editor_path: Option<std::path::PathBuf>,
+    endless: Option<generator::GeneratorConfig>,
+) {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: Locale::default().get("window.title").to_string(),
+                resolution: (ARENA_WIDTH, ARENA_HEIGHT).into(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .insert_resource(ClearColor(Color::BLACK))
+        .init_resource::<GameState>()
+        .init_resource::<EffectSettings>()
+        .init_resource::<Scoring>()
+        .init_resource::<Theme>()
+        .init_resource::<PlayerInput>()
+        .init_resource::<GameRng>()
+        .insert_resource(level)
+        .insert_resource(coop)
+        .add_event::<ImpactEvent>()
+        .add_plugin(ConfigPlugin)
+        .add_plugin(JuicePlugin)
+        .add_plugin(ParticlePlugin)
+        .add_plugin(ArtPlugin)
+        .add_plugin(SoundPlugin)
+        .add_plugin(MusicPlugin)
+        .add_plugin(PopupPlugin)
+        .add_plugin(LocalePlugin)
+        .add_plugin(FontPlugin)
+        .add_plugin(SettingsPlugin)
+        .add_plugin(AutopilotPlugin)
+        .add_plugin(CoopPlugin)
+        .add_plugin(EditorPlugin { path: editor_path })
+        .add_plugin(EndlessPlugin { generator: endless })
+        .add_plugin(HighScorePlugin)
+        .add_startup_system(setup)
+        .add_system(read_player_input.in_set(PlayerInputSet))
+        .add_system(paddle_movement.after(PlayerInputSet).run_if(gameplay_active))
+        .add_system(apply_player_actions.after(PlayerInputSet))
+        .add_system(move_ball.run_if(gameplay_active))
+        .add_system(check_collisions.run_if(gameplay_active))
+        .add_system(update_brick_damage.after(check_collisions))
+        .add_system(update_level_progress.after(check_collisions).run_if(gameplay_active))
+        .add_system(update_scoreboard)
+        .add_system(update_help_text)
+        .add_system(handle_restart)
+        .add_system(handle_input)
+        .add_system(toggle_fullscreen)
+        .add_system(check_win_condition.after(update_level_progress))
+        .add_system(update_ball_trail)
+        .add_system(render_ball_trail.after(update_ball_trail))
+        .add_system(animate_background)
+        .add_system(animate_paddle_glow)
+        .add_system(show_game_over_modal)
+        .add_system(apply_theme);
+    if let Some(address) = spectate {
+        app.add_plugin(SpectatorPlugin { address });
+    }
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    level: Res<Level>,
+    coop: Res<Coop>,
+    mut rng: ResMut<GameRng>,
+) {
+    let colors = theme.colors();
+    let sizes = theme.sizes();
+    let fonts = theme.fonts();
+    coop.reset_lives(&mut game_state, config.initial_lives);
+
+    // 相机：保证整个游戏区域可见，窗口比例不同时两侧或上下留黑边
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: ARENA_WIDTH,
+        min_height: ARENA_HEIGHT,
+    };
+    // 背景等精灵使用负的 z 值，远平面需要覆盖到它们
+    camera.projection.far = 1100.0;
+    commands.spawn(camera);
+
+    // 黑边遮罩，挡住游戏区域外飞出的粒子等
+    spawn_letterbox(&mut commands);
+
+    // 背景渐变效果
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: colors.background,
+                custom_size: Some(Vec2::new(ARENA_WIDTH, ARENA_HEIGHT)),
+                ..default()
+            },
+            texture: background_texture(&theme, &asset_server),
+            transform: Transform::from_xyz(0.0, 0.0, -10.0),
+            ..default()
+        },
+        Background,
+    ));
+
+    // 挡板 - 美化版本，双人合作时每位玩家一块
+    for player in 0..coop.players {
+        spawn_paddle(&mut commands, player, &coop, &theme, &config);
+    }
+
+    // 球
+    spawn_new_ball(&mut commands, &mut game_state, &theme, &config, &mut rng);
+
+    // 球的发光效果
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: colors.ball.with_a(0.4),
+                custom_size: Some(Vec2::splat(BALL_SIZE + sizes.ball_glow)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, -1.0),
+            ..default()
+        },
+        BallGlow,
+    ));
+
+    // 砖块
+    spawn_brick_wall(&mut commands, &theme, &config, &level);
+
+    // 分数文本
+    commands.spawn((
+        TextBundle::from_section(
+            locale.format("hud.score", &[("score", &0)]),
+            TextStyle {
+                font: Default::default(),
+                font_size: fonts.hud,
+                color: colors.text_primary,
+            },
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(15.0),
+                left: Val::Px(15.0),
+                ..default()
+            },
+            ..default()
+        }),
+        ScoreText,
+    ));
+
+    // 生命文本 - 显示在右上角
+    commands.spawn((
+        TextBundle::from_section(
+            locale.format("hud.lives", &[("lives", &config.initial_lives)]),
+            TextStyle {
+                font: Default::default(),
+                font_size: fonts.hud,
+                color: colors.text_lives,
+            },
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(15.0),
+                right: Val::Px(15.0),
+                ..default()
+            },
+            ..default()
+        }),
+        LivesText,
+    ));
+    
+    // 游戏说明文本
+    commands.spawn((
+        TextBundle::from_section(
+            locale.get("hud.help"),
+            TextStyle {
+                font: Default::default(),
+                font_size: fonts.help,
+                color: colors.text_info,
+            },
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(55.0),
+                left: Val::Px(15.0),
+                ..default()
+            },
+            ..default()
+        }),
+        HelpText,
+    ));
+
+    // 游戏结束大字体文本（居中显示）
+    commands.spawn(centered_row(46.0)).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                locale.format("status.playing", &[("score", &0)]),
+                TextStyle {
+                    font: Default::default(),
+                    font_size: fonts.hud,
+                    color: colors.text_good,
+                },
+            ).with_text_alignment(TextAlignment::Center),
+            GameOverText,
+        ));
+    });
+}
+
+// 挡板及其发光效果，位置和颜色由合作模式决定
+fn spawn_paddle(commands: &mut Commands, player: usize, coop: &Coop, theme: &Theme, config: &GameConfig) {
+    let colors = theme.colors();
+    let color = coop.tint(colors, player, colors.paddle);
+    let home = coop.paddle_home(player);
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::new(config.paddle_width, PADDLE_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_xyz(home.x, home.y, 0.0),
+            ..default()
+        },
+        Paddle { width: config.paddle_width, player },
+    )).with_children(|parent| {
+        // 贴图模式下的两端端盖，纯色模式下隐藏
+        for side in [-1.0, 1.0] {
+            parent.spawn((
+                SpriteBundle {
+                    transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                PaddleCap { side },
+            ));
+        }
+    });
+
+    // 挡板发光效果
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: color.with_a(0.3),
+                custom_size: Some(Vec2::new(config.paddle_width, PADDLE_HEIGHT) + theme.sizes().paddle_glow),
+                ..default()
+            },
+            transform: Transform::from_xyz(home.x, home.y, -1.0),
+            ..default()
+        },
+        PaddleGlow { player },
+    ));
+}
+
+// 横向居中的全宽UI容器，top_percent 为距窗口顶部的百分比
+fn centered_row(top_percent: f32) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: Size::new(Val::Percent(100.0), Val::Auto),
+            position: UiRect {
+                top: Val::Percent(top_percent),
+                ..default()
+            },
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+// 在游戏区域四周生成足够大的黑色遮罩
+fn spawn_letterbox(commands: &mut Commands) {
+    let thickness = ARENA_WIDTH.max(ARENA_HEIGHT) * 4.0;
+    let bars = [
+        (Vec2::new(-(ARENA_WIDTH + thickness) / 2.0, 0.0), Vec2::new(thickness, thickness * 2.0)),
+        (Vec2::new((ARENA_WIDTH + thickness) / 2.0, 0.0), Vec2::new(thickness, thickness * 2.0)),
+        (Vec2::new(0.0, (ARENA_HEIGHT + thickness) / 2.0), Vec2::new(ARENA_WIDTH, thickness)),
+        (Vec2::new(0.0, -(ARENA_HEIGHT + thickness) / 2.0), Vec2::new(ARENA_WIDTH, thickness)),
+    ];
+    for (position, size) in bars {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(900.0)),
+            ..default()
+        });
+    }
+}
+
+// F11 切换全屏
+fn toggle_fullscreen(
+    keyboard: Res<Input<KeyCode>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F11) {
+        return;
+    }
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window.mode = match window.mode {
+            WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+            _ => WindowMode::Windowed,
+        };
+    }
+}
+
+// 键盘和手柄操作写入 PlayerInput，自动驾驶接管时由它写入
+// 单人时 ← → 控制挡板；双人时玩家 1 用 A/D、玩家 2 用 ← →
+// 手柄按连接顺序对应玩家，左摇杆或十字键移动，A 键加球，Start 键重新开始
+fn read_player_input(
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    autopilot: Res<autopilot::Autopilot>,
+    coop: Res<Coop>,
+    mut input: ResMut<PlayerInput>,
+) {
+    if autopilot.active() {
+        return;
+    }
+
+    let key_sets: &[(KeyCode, KeyCode)] = if coop.enabled() {
+        &[(KeyCode::A, KeyCode::D), (KeyCode::Left, KeyCode::Right)]
+    } else {
+        &[(KeyCode::Left, KeyCode::Right)]
+    };
+    let mut direction = [0.0; MAX_PLAYERS];
+    for (player, &(left, right)) in key_sets.iter().enumerate() {
+        if keyboard.pressed(left) { direction[player] -= 1.0; }
+        if keyboard.pressed(right) { direction[player] += 1.0; }
+    }
+
+    let mut add_ball = keyboard.just_pressed(KeyCode::Space);
+    let mut restart = keyboard.just_pressed(KeyCode::R);
+    for (index, gamepad) in gamepads.iter().enumerate() {
+        let player = if coop.enabled() { index } else { 0 };
+        if player >= coop.players {
+            continue;
+        }
+        let button = |button_type| GamepadButton::new(gamepad, button_type);
+        let stick = gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0);
+        if stick.abs() > STICK_DEADZONE { direction[player] += stick; }
+        if gamepad_buttons.pressed(button(GamepadButtonType::DPadLeft)) { direction[player] -= 1.0; }
+        if gamepad_buttons.pressed(button(GamepadButtonType::DPadRight)) { direction[player] += 1.0; }
+        add_ball |= gamepad_buttons.just_pressed(button(GamepadButtonType::South));
+        restart |= gamepad_buttons.just_pressed(button(GamepadButtonType::Start));
+    }
+
+    *input = PlayerInput {
+        direction,
+        add_ball,
+        restart,
+    };
+}
+
+fn paddle_movement(
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    config: Res<GameConfig>,
+    coop: Res<Coop>,
+    game_state: Res<GameState>,
+    mut paddle_query: Query<(&mut Transform, &Paddle)>,
+) {
+    for (mut transform, paddle) in paddle_query.iter_mut() {
+        if coop.is_out(&game_state, paddle.player) {
+            continue;
+        }
+        let direction = input.direction[paddle.player].clamp(-1.0, 1.0);
+        let (min_x, max_x) = coop.paddle_range(paddle.player, paddle.width);
+
+        let new_x = transform.translation.x + direction * config.paddle_speed * time.delta_seconds();
+        transform.translation.x = new_x.clamp(min_x, max_x);
+    }
+}
+
+fn move_ball(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut ball_query: Query<(Entity, &mut Transform, &mut Ball)>,
+    paddle_query: Query<(&Transform, &Paddle), Without<Ball>>,
+    mut game_state: ResMut<GameState>,
+    hit_stop: Res<HitStop>,
+    mut impact_events: EventWriter<ImpactEvent>,
+    mut scoring: ResMut<Scoring>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    coop: Res<Coop>,
+    mut rng: ResMut<GameRng>,
+) {
+    if game_state.game_over || hit_stop.active() { return; }
+
+    let delta = time.delta_seconds();
+    let mut balls_to_remove = Vec::new();
+    let mut last_fall = None;  // 同一帧掉落多个球时取编号最大的，与遍历顺序无关
+
+    for (entity, mut transform, mut ball) in ball_query.iter_mut() {
+        // 更新球的位置
+        transform.translation.x += ball.velocity.x * delta;
+        transform.translation.y += ball.velocity.y * delta;
+
+        // 左右边界反弹，按所在一侧决定方向，避免速度很小时在墙外来回翻转
+        if (transform.translation.x < -ARENA_WIDTH/2.0 + BALL_SIZE/2.0 && ball.velocity.x < 0.0)
+            || (transform.translation.x > ARENA_WIDTH/2.0 - BALL_SIZE/2.0 && ball.velocity.x > 0.0) {
+            ball.velocity.x *= -1.0;
+            impact_events.send(ImpactEvent {
+                kind: ImpactKind::Wall,
+                weight: 0.1,
+                entity: None,
+            });
+        }
+
+        // 上边界反弹
+        if transform.translation.y > ARENA_HEIGHT/2.0 - BALL_SIZE/2.0 && ball.velocity.y > 0.0 {
+            ball.velocity.y *= -1.0;
+            impact_events.send(ImpactEvent {
+                kind: ImpactKind::Wall,
+                weight: 0.1,
+                entity: None,
+            });
+        }
+
+        // 下边界处理 - 球掉出边界
+        if transform.translation.y < -ARENA_HEIGHT/2.0 {
+            balls_to_remove.push(entity);
+            if last_fall.is_none_or(|(id, _)| ball.id > id) {
+                last_fall = Some((ball.id, transform.translation.x));
+            }
+        }
+    }
+
+    // 移除掉出边界的球
+    let balls_to_remove_count = balls_to_remove.len();
+    for entity in balls_to_remove {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // 检查是否还有球在场上
+    let remaining_balls = ball_query.iter().count() - balls_to_remove_count;
+    
+    if remaining_balls == 0 {
+        // 所有球都掉出了边界
+        impact_events.send(ImpactEvent {
+            kind: ImpactKind::LifeLost,
+            weight: 1.5,
+            entity: None,
+        });
+        scoring.reset_combo();
+
+        // 分开计算生命时，由离最后掉落的球最近的在场玩家承担
+        if !coop.shared_lives {
+            let last_fall_x = last_fall.map_or(0.0, |(_, x)| x);
+            let missed_by = paddle_query
+                .iter()
+                .filter(|(_, paddle)| !coop.is_out(&game_state, paddle.player))
+                .min_by(|a, b| {
+                    (a.0.translation.x - last_fall_x).abs().total_cmp(&(b.0.translation.x - last_fall_x).abs())
+                })
+                .map(|(_, paddle)| paddle.player);
+            if let Some(player) = missed_by {
+                game_state.player_lives[player] -= 1;
+                if game_state.player_lives[player] == 0 {
+                    info!("玩家 {} 生命耗尽，退场", player + 1);
+                }
+            }
+        }
+
+        if game_state.lives > 1 {
+            // 还有生命，减少生命并重新开始
+            game_state.lives -= 1;
+            info!("失去一条生命！剩余生命: {}", game_state.lives);
+            
+            // 重新创建一个球
+            spawn_new_ball(&mut commands, &mut game_state, &theme, &config, &mut rng);
+        } else {
+            // 没有生命了，游戏结束
+            game_state.game_over = true;
+            info!("游戏结束！所有生命耗尽，最终分数: {}", game_state.score);
+        }
+    }
+}
+
+// 创建新球的辅助函数
+fn spawn_new_ball(
+    commands: &mut Commands,
+    game_state: &mut GameState,
+    theme: &Theme,
+    config: &GameConfig,
+    rng: &mut GameRng,
+) {
+    let direction_x = rng.0.gen_range(-0.8..0.8);
+    let direction_y = 1.0; // 向上开始
+    
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.colors().ball,
+                custom_size: Some(Vec2::new(BALL_SIZE, BALL_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 0.0), // 从中心开始
+            ..default()
+        },
+        Ball {
+            velocity: Vec2::new(direction_x, direction_y) * config.ball_speed,
+            id: game_state.next_ball_id,
+            last_player: None,
+        },
+        Trail {
+            positions: VecDeque::with_capacity(config.trail_length),
+            max_length: config.trail_length,
+            sample_timer: 0.0,
+        },
+    )).with_children(|parent| {
+        // 预先创建轨迹点精灵，渲染时只更新位置和颜色
+        for index in 0..config.trail_length {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: trail_color(config.ball_speed, config.ball_speed, theme),
+                        custom_size: Some(Vec2::new(BALL_SIZE, BALL_SIZE)),
+                        ..default()
+                    },
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                TrailDot { index },
+            ));
+        }
+    });
+    
+    game_state.next_ball_id += 1;
+}
+
+// 简化碰撞检测，避免复杂查询
+fn check_collisions(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    mut ball_query: Query<(Entity, &mut Transform, &mut Ball), Without<Paddle>>,
+    paddle_query: Query<(Entity, &Transform, &Paddle)>,
+    mut brick_query: Query<(Entity, &Transform, &Sprite, &mut Brick), Without<Ball>>,
+    hit_stop: Res<HitStop>,
+    mut impact_events: EventWriter<ImpactEvent>,
+    mut particle_bursts: EventWriter<ParticleBurst>,
+    mut popup_events: EventWriter<ScorePopupEvent>,
+    mut scoring: ResMut<Scoring>,
+    config: Res<GameConfig>,
+    coop: Res<Coop>,
+) {
+    if game_state.game_over || hit_stop.active() { return; }
+
+    let ball_radius = BALL_SIZE / 2.0;
+    let paddle_half_h = PADDLE_HEIGHT / 2.0;
+
+    // 球按编号、砖块按行和 x 坐标、挡板按玩家的固定顺序处理，结果与实体在内存中的排列无关（联机回滚依赖这一点）
+    let mut ball_order: Vec<(u32, Entity)> = ball_query.iter().map(|(entity, _, ball)| (ball.id, entity)).collect();
+    ball_order.sort_unstable();
+    let mut brick_order: Vec<(u32, f32, Entity)> = brick_query
+        .iter()
+        .map(|(entity, transform, _, brick)| (brick.row, transform.translation.x, entity))
+        .collect();
+    brick_order.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    let mut paddles: Vec<_> = paddle_query.iter().collect();
+    paddles.sort_unstable_by_key(|(_, _, paddle)| paddle.player);
+
+    // 为每个球检测碰撞
+    for &(_, ball_entity) in &ball_order {
+        let Ok((_, mut ball_transform, mut ball)) = ball_query.get_mut(ball_entity) else { continue };
+        // 挡板碰撞检测，已退场的玩家不再接球
+        for &(paddle_entity, paddle_transform, paddle) in &paddles {
+            if coop.is_out(&game_state, paddle.player) {
+                continue;
+            }
+            let paddle_half_w = paddle.width / 2.0;
+            if (ball_transform.translation.x - paddle_transform.translation.x).abs() <= paddle_half_w + ball_radius
+                && (ball_transform.translation.y - paddle_transform.translation.y).abs() <= paddle_half_h + ball_radius
+                && ball.velocity.y < 0.0 {
+                // 球碰到挡板，反弹
+                ball.velocity.y *= -1.0;
+
+                // 根据球碰到挡板的位置调整反弹角度
+                let hit_pos = (ball_transform.translation.x - paddle_transform.translation.x) / paddle_half_w;
+                ball.velocity.x = hit_pos * config.ball_speed * 0.75;
+
+                // 确保球不会卡在挡板里
+                ball_transform.translation.y = paddle_transform.translation.y + paddle_half_h + ball_radius + 1.0;
+                ball.last_player = Some(paddle.player);
+                scoring.reset_combo();
+
+                impact_events.send(ImpactEvent {
+                    kind: ImpactKind::Paddle,
+                    weight: 0.3,
+                    entity: Some(paddle_entity),
+                });
+                break;
+            }
+        }
+
+        // 砖块碰撞检测
+        for &(_, _, brick_entity) in &brick_order {
+            let Ok((_, brick_transform, brick_sprite, mut brick)) = brick_query.get_mut(brick_entity) else { continue };
+            // 同一帧内已被其他球击碎的砖块
+            if brick.hp == 0 {
+                continue;
+            }
+
+            let brick_half_w = brick.size.x / 2.0;
+            let brick_half_h = brick.size.y / 2.0;
+            if (ball_transform.translation.x - brick_transform.translation.x).abs() <= brick_half_w + ball_radius
+                && (ball_transform.translation.y - brick_transform.translation.y).abs() <= brick_half_h + ball_radius {
+                
+                // 不可击碎的砖块按穿入较浅的一边反弹并把球推出去，否则从侧面进入的球会一直留在砖块里
+                if brick.indestructible {
+                    let offset = ball_transform.translation.truncate() - brick_transform.translation.truncate();
+                    let depth = Vec2::new(brick_half_w, brick_half_h) + ball_radius - offset.abs();
+                    if depth.x < depth.y {
+                        ball.velocity.x = ball.velocity.x.abs().copysign(offset.x);
+                        ball_transform.translation.x += depth.x.copysign(offset.x);
+                    } else {
+                        ball.velocity.y = ball.velocity.y.abs().copysign(offset.y);
+                        ball_transform.translation.y += depth.y.copysign(offset.y);
+                    }
+                    impact_events.send(ImpactEvent {
+                        kind: ImpactKind::BrickHit,
+                        weight: 0.2,
+                        entity: Some(brick_entity),
+                    });
+                    break;
+                }
+
+                ball.velocity.y *= -1.0; // 简化：只上下反弹
+
+                // 耐久未耗尽，只扣除耐久
+                brick.hp -= 1;
+                if brick.hp > 0 {
+                    impact_events.send(ImpactEvent {
+                        kind: ImpactKind::BrickHit,
+                        weight: 0.4,
+                        entity: Some(brick_entity),
+                    });
+                    break;
+                }
+
+                // 分值越高的砖块冲击越强
+                impact_events.send(ImpactEvent {
+                    kind: ImpactKind::BrickBreak,
+                    weight: 0.4 + brick.points as f32 / 100.0,
+                    entity: Some(brick_entity),
+                });
+
+                // 创建砖块颜色的粒子爆炸效果
+                particle_bursts.send(ParticleBurst {
+                    position: brick_transform.translation,
+                    emitter: ParticleEmitter::brick_burst(brick_sprite.color),
+                });
+                
+                // 创建分数弹框
+                let (awarded, multiplier) = scoring.register_brick(brick.points);
+                popup_events.send(ScorePopupEvent {
+                    position: brick_transform.translation,
+                    points: brick.points,
+                    multiplier,
+                    color: brick_sprite.color,
+                });
+                
+                commands.entity(brick_entity).despawn_recursive();
+                game_state.score += awarded;
+                if let Some(player) = ball.last_player {
+                    game_state.player_scores[player] += awarded;
+                }
+                break;
+            }
+        }
+    }
+}
+
+fn update_scoreboard(
+    game_state: Res<GameState>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    mut score_query: Query<&mut Text, (With<ScoreText>, Without<GameOverText>, Without<LivesText>)>,
+    mut lives_query: Query<&mut Text, (With<LivesText>, Without<ScoreText>, Without<GameOverText>)>,
+    mut game_over_query: Query<&mut Text, (With<GameOverText>, Without<ScoreText>, Without<LivesText>)>,
+) {
+    if game_state.is_changed() || locale.is_changed() || theme.is_changed() {
+        let colors = theme.colors();
+        let fonts = theme.fonts();
+        let score: &dyn std::fmt::Display = &game_state.score;
+
+        // 更新顶部分数文本
+        let mut score_text = score_query.single_mut();
+        score_text.sections[0].style.font_size = fonts.hud;
+        score_text.sections[0].value = if game_state.won {
+            locale.format("hud.score_won", &[("score", score)])
+        } else if game_state.game_over {
+            locale.format("hud.score_game_over", &[("score", score)])
+        } else {
+            locale.format("hud.score", &[("score", score)])
+        };
+        
+        // 根据状态改变文字颜色
+        score_text.sections[0].style.color = if game_state.won {
+            colors.text_good // 胜利
+        } else if game_state.game_over {
+            colors.text_bad // 失败
+        } else {
+            colors.text_primary // 正常
+        };
+
+        // 更新生命文本
+        let mut lives_text = lives_query.single_mut();
+        lives_text.sections[0].style.font_size = fonts.hud;
+        lives_text.sections[0].value = locale.format("hud.lives", &[("lives", &game_state.lives)]);
+        
+        // 根据生命数改变颜色
+        lives_text.sections[0].style.color = if game_state.lives <= 1 {
+            colors.text_bad // 告急
+        } else if game_state.lives == 2 {
+            colors.text_warn // 警告
+        } else {
+            colors.text_lives // 正常
+        };
+
+        // 更新中央游戏结束文本 - 使用更大更明显的显示
+        for mut game_over_text in game_over_query.iter_mut() {
+            if game_state.won {
+                game_over_text.sections[0].value = locale.format("status.victory", &[("score", score)]);
+                game_over_text.sections[0].style.color = colors.text_primary;
+                game_over_text.sections[0].style.font_size = fonts.headline;
+            } else if game_state.game_over {
+                game_over_text.sections[0].value = locale.format("status.game_over", &[("score", score)]);
+                game_over_text.sections[0].style.color = colors.text_bad;
+                game_over_text.sections[0].style.font_size = fonts.headline;
+            } else {
+                game_over_text.sections[0].value = locale.format("status.playing", &[("score", score)]);
+                game_over_text.sections[0].style.color = colors.text_good;
+                game_over_text.sections[0].style.font_size = fonts.hud;
+            }
+        }
+    }
+}
+
+// 切换语言或主题后更新说明文本
+fn update_help_text(
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    mut help_query: Query<&mut Text, With<HelpText>>,
+) {
+    if locale.is_changed() || theme.is_changed() {
+        for mut text in help_query.iter_mut() {
+            text.sections[0].value = locale.get("hud.help").to_string();
+            text.sections[0].style.color = theme.colors().text_info;
+            text.sections[0].style.font_size = theme.fonts().help;
+        }
+    }
+}
+
+// 重新开始和加球是玩家操作，键盘和自动驾驶都通过 PlayerInput 触发
+fn apply_player_actions(
+    input: Res<PlayerInput>,
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    ball_query: Query<&Ball>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    mut sfx_events: EventWriter<SfxEvent>,
+) {
+    if input.restart && (game_state.game_over || game_state.won) {
+        game_state.restart_requested = true;
+    }
+
+    // 多球模式：按空格键添加新球（最多5个球）
+    if input.add_ball && !game_state.game_over {
+        let current_ball_count = ball_query.iter().count();
+        if current_ball_count < config.max_balls {
+            spawn_new_ball(&mut commands, &mut game_state, &theme, &config, &mut rng);
+            sfx_events.send(SfxEvent::new(Sfx::PowerUp));
+            info!("添加新球！当前球数: {}", current_ball_count + 1);
+        } else {
+            info!("已达到最大球数限制: {}", config.max_balls);
+        }
+    }
+}
+
+fn handle_input(
+    keyboard: Res<Input<KeyCode>>,
+    mut game_state: ResMut<GameState>,
+    mut exit: EventWriter<bevy::app::AppExit>,
+    mut effect_settings: ResMut<EffectSettings>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        exit.send(bevy::app::AppExit);
+    }
+    
+    // 测试用：按G键触发游戏结束，按W键触发胜利
+    if keyboard.just_pressed(KeyCode::G) && !game_state.game_over {
+        game_state.game_over = true;
+        game_state.debug_ending = true;
+        game_state.score += 100; // 添加一些分数用于测试
+        println!("\n 测试游戏结束！");
+        println!(" 手动触发游戏结束");
+        println!(" 最终分数: {}", game_state.score);
+        println!(" 按 R 键重新开始，按 ESC 键退出");
+    }
+    
+    if keyboard.just_pressed(KeyCode::W) && !game_state.game_over {
+        game_state.won = true;
+        game_state.game_over = true;
+        game_state.debug_ending = true;
+        game_state.score += 500; // 胜利奖励分数
+        println!(" 恭喜胜利！");
+        println!(" 你赢了！所有砖块已消除！");
+        println!(" 最终分数: {}", game_state.score);
+        println!(" 按 R 键重新开始，按 ESC 键退出");
+    }
+    
+    // 按E键切换减少特效模式
+    if keyboard.just_pressed(KeyCode::E) {
+        effect_settings.reduced_effects = !effect_settings.reduced_effects;
+        println!(" 减少特效模式: {}", if effect_settings.reduced_effects { "开" } else { "关" });
+    }
+}
+
+fn handle_restart(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    ball_query: Query<Entity, With<Ball>>,
+    mut paddle_query: Query<(&mut Transform, &Paddle), Without<Ball>>,
+    brick_query: Query<Entity, With<Brick>>,
+    modal_query: Query<Entity, With<GameOverModal>>,
+    bg_query: Query<Entity, With<ModalBackground>>,
+    popup_query: Query<Entity, With<ScorePopup>>,
+    mut scoring: ResMut<Scoring>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    level: Res<Level>,
+    coop: Res<Coop>,
+    mut rng: ResMut<GameRng>,
+) {
+    if !game_state.restart_requested {
+        return;
+    }
+    
+    // 清理所有UI元素
+    for entity in modal_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in bg_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in popup_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    
+    // 重置游戏状态
+    game_state.score = 0;
+    game_state.player_scores = [0; MAX_PLAYERS];
+    coop.reset_lives(&mut game_state, config.initial_lives);
+    game_state.game_over = false;
+    game_state.won = false;
+    game_state.debug_ending = false;
+    game_state.restart_requested = false;
+    game_state.next_ball_id = 1;
+    scoring.reset();
+    
+    // 删除所有现有的球
+    for entity in ball_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    
+    // 创建一个新球
+    spawn_new_ball(&mut commands, &mut game_state, &theme, &config, &mut rng);
+    
+    // 重置挡板位置
+    for (mut paddle_transform, paddle) in paddle_query.iter_mut() {
+        paddle_transform.translation.x = coop.paddle_home(paddle.player).x;
+    }
+    
+    // 删除所有现有砖块（连同边框等子实体）
+    for brick_entity in brick_query.iter() {
+        commands.entity(brick_entity).despawn_recursive();
+    }
+    
+    // 重新生成砖块
+    spawn_brick_wall(&mut commands, &theme, &config, &level);
+}
+
+// 按关卡生成整面砖块墙
+fn spawn_brick_wall(commands: &mut Commands, theme: &Theme, config: &GameConfig, level: &Level) {
+    let size = Vec2::new(config.brick_width, config.brick_height);
+    for (row, col, brick) in level.bricks() {
+        let position = brick_cell_position(config, row, col);
+        spawn_brick(commands, theme, position, size, brick, row as u32);
+    }
+}
+
+// 砖块网格中一格的中心，BRICK_COLS 列居中排列，第 0 行在顶部
+fn brick_cell_position(config: &GameConfig, row: usize, col: usize) -> Vec2 {
+    let total_width = BRICK_COLS as f32 * (config.brick_width + BRICK_SPACING) - BRICK_SPACING;
+    let start_x = -total_width / 2.0 + config.brick_width / 2.0;
+    let start_y = ARENA_HEIGHT / 2.0 - 50.0;
+    Vec2::new(
+        start_x + col as f32 * (config.brick_width + BRICK_SPACING),
+        start_y - row as f32 * (config.brick_height + BRICK_SPACING),
+    )
+}
+
+// 生成单个砖块，边框、裂纹和标签都作为子实体
+fn spawn_brick(
+    commands: &mut Commands,
+    theme: &Theme,
+    position: Vec2,
+    size: Vec2,
+    def: BrickDef,
+    row: u32,
+) -> Entity {
+    let colors = theme.colors();
+    let (border_color, border_extra) = theme.brick_border();
+    let brick = Brick {
+        points: def.points,
+        hp: def.hp,
+        max_hp: def.hp,
+        row,
+        size,
+        indestructible: def.indestructible,
+    };
+    let (hp, points) = (brick.hp, brick.points);
+    let show_hp = hp > 1 && !brick.indestructible;
+    // 不可击碎的砖块没有分值，标记为 #
+    let glyph = if brick.indestructible { "#".to_string() } else { points.to_string() };
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: brick.color(theme),
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..default()
+        },
+        brick,
+    )).with_children(|parent| {
+        // 砖块边框效果
+        parent.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: border_color,
+                    custom_size: Some(size + border_extra),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, -0.1),
+                ..default()
+            },
+            BrickBorder,
+        ));
+
+        // 裂纹覆盖层，受损后才显示
+        parent.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: colors.crack,
+                    custom_size: Some(Vec2::new(size.x * 0.6, 2.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, 0.1)
+                    .with_rotation(Quat::from_rotation_z(0.25)),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            BrickCrack,
+        ));
+
+        // 耐久标签，只有多次击打的砖块才显示
+        parent.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    hp.to_string(),
+                    TextStyle {
+                        font: Default::default(),
+                        font_size: theme.fonts().brick_label,
+                        color: colors.brick_label,
+                    },
+                ),
+                transform: Transform::from_xyz(size.x / 2.0 - 8.0, 0.0, 0.2),
+                visibility: if show_hp { Visibility::Inherited } else { Visibility::Hidden },
+                ..default()
+            },
+            BrickHpLabel,
+        ));
+
+        // 分值标记
+        parent.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    glyph,
+                    TextStyle {
+                        font: Default::default(),
+                        font_size: theme.fonts().brick_label,
+                        color: colors.brick_label,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, 0.0, 0.2),
+                visibility: if theme.brick_glyphs { Visibility::Inherited } else { Visibility::Hidden },
+                ..default()
+            },
+            BrickGlyph,
+        ));
+    }).id()
+}
+
+// 主题或色觉模式改变时给场景中已有的实体重新上色
+fn apply_theme(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    coop: Res<Coop>,
+    asset_server: Res<AssetServer>,
+    brick_query: Query<(Entity, &Brick, &Children)>,
+    flashing_query: Query<Entity, With<Flash>>,
+    mut sprites: ParamSet<(
+        Query<&mut Sprite, With<Brick>>,
+        Query<&mut Sprite, With<BrickBorder>>,
+        Query<&mut Sprite, With<BrickCrack>>,
+        Query<(&mut Sprite, &Paddle)>,
+        Query<(&mut Sprite, &PaddleGlow)>,
+        Query<&mut Sprite, With<Ball>>,
+        Query<&mut Sprite, With<BallGlow>>,
+    )>,
+    mut background_query: Query<&mut Handle<Image>, With<Background>>,
+    mut label_query: Query<&mut Text, Or<(With<BrickHpLabel>, With<BrickGlyph>)>>,
+    mut glyph_query: Query<&mut Visibility, With<BrickGlyph>>,
+) {
+    if !theme.is_changed() || theme.is_added() {
+        return;
+    }
+    let colors = theme.colors();
+    let sizes = theme.sizes();
+
+    // 闪白结束时会恢复旧颜色，直接结束正在进行的闪白
+    for entity in flashing_query.iter() {
+        commands.entity(entity).remove::<Flash>();
+    }
+    let (border_color, border_extra) = theme.brick_border();
+    for (entity, brick, children) in brick_query.iter() {
+        if let Ok(mut sprite) = sprites.p0().get_mut(entity) {
+            sprite.color = brick.color(&theme);
+        }
+        for &child in children.iter() {
+            if let Ok(mut sprite) = sprites.p1().get_mut(child) {
+                sprite.color = border_color;
+                sprite.custom_size = Some(brick.size + border_extra);
+            }
+        }
+    }
+    for mut sprite in sprites.p2().iter_mut() {
+        sprite.color = colors.crack;
+    }
+    for (mut sprite, paddle) in sprites.p3().iter_mut() {
+        sprite.color = coop.tint(colors, paddle.player, colors.paddle);
+    }
+    // 发光的透明度由动画控制，这里只换颜色
+    for (mut sprite, glow) in sprites.p4().iter_mut() {
+        let alpha = sprite.color.a();
+        sprite.color = coop.tint(colors, glow.player, colors.paddle).with_a(alpha);
+    }
+    for mut sprite in sprites.p5().iter_mut() {
+        sprite.color = colors.ball;
+    }
+    for mut sprite in sprites.p6().iter_mut() {
+        let alpha = sprite.color.a();
+        sprite.color = colors.ball.with_a(alpha);
+        sprite.custom_size = Some(Vec2::splat(BALL_SIZE + sizes.ball_glow));
+    }
+    for mut texture in background_query.iter_mut() {
+        *texture = background_texture(&theme, &asset_server);
+    }
+
+    for mut text in label_query.iter_mut() {
+        text.sections[0].style.color = colors.brick_label;
+        text.sections[0].style.font_size = theme.fonts().brick_label;
+    }
+    for mut visibility in glyph_query.iter_mut() {
+        *visibility = if theme.brick_glyphs { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
+
+// 主题指定了背景图片时加载它，否则用默认的纯白贴图，颜色直接作为背景色
+fn background_texture(theme: &Theme, asset_server: &AssetServer) -> Handle<Image> {
+    match &theme.def().background.texture {
+        Some(path) => asset_server.load(path.as_str()),
+        None => Handle::default(),
+    }
+}
+
+// 砖块受损后更新裂纹和耐久标签
+fn update_brick_damage(
+    brick_query: Query<(&Brick, &Children), Changed<Brick>>,
+    mut crack_query: Query<&mut Visibility, (With<BrickCrack>, Without<BrickHpLabel>)>,
+    mut label_query: Query<(&mut Text, &mut Visibility), (With<BrickHpLabel>, Without<BrickCrack>)>,
+) {
+    for (brick, children) in brick_query.iter() {
+        for &child in children.iter() {
+            if let Ok(mut visibility) = crack_query.get_mut(child) {
+                *visibility = if brick.hp < brick.max_hp { Visibility::Inherited } else { Visibility::Hidden };
+            }
+            if let Ok((mut text, mut visibility)) = label_query.get_mut(child) {
+                text.sections[0].value = brick.hp.to_string();
+                *visibility = if brick.hp > 1 && !brick.indestructible { Visibility::Inherited } else { Visibility::Hidden };
+            }
+        }
+    }
+}
+
+fn check_win_condition(
+    mut game_state: ResMut<GameState>,
+    mut scoring: ResMut<Scoring>,
+    endless: Option<Res<Endless>>,
+    brick_query: Query<&Brick>,
+) {
+    // 无尽模式没有通关，砖块清空时补上新的一行
+    if game_state.game_over || game_state.won || endless.is_some_and(|endless| endless.active) {
+        return;
+    }
+    
+    // 检查是否还有可以击碎的砖块
+    if brick_query.iter().all(|brick| brick.indestructible) {
+        // 通关结算：速通奖励和剩余生命奖励
+        let bonus = scoring.finish_level(game_state.lives);
+        game_state.score += bonus;
+        game_state.won = true;
+        game_state.game_over = true; // 游戏结束，但是胜利状态
+        info!("完美通关！所有砖块已消除，最终分数: {}", game_state.score);
+    }
+}
+
+// 记录关卡用时，并为刚被清空的整行发放奖励
+fn update_level_progress(
+    time: Res<Time>,
+    theme: Res<Theme>,
+    mut game_state: ResMut<GameState>,
+    mut scoring: ResMut<Scoring>,
+    brick_query: Query<(&Transform, &Brick)>,
+    mut popup_events: EventWriter<ScorePopupEvent>,
+) {
+    if game_state.game_over {
+        return;
+    }
+
+    scoring.level_time += time.delta_seconds();
+
+    let mut rows = HashMap::new();
+    for (transform, brick) in brick_query.iter() {
+        if brick.hp > 0 && !brick.indestructible {
+            rows.insert(brick.row, transform.translation.y);
+        }
+    }
+    for (y, bonus) in scoring.update_rows(&rows) {
+        game_state.score += bonus;
+        popup_events.send(ScorePopupEvent {
+            position: Vec3::new(0.0, y, 0.0),
+            points: bonus,
+            multiplier: 1,
+            color: theme.colors().text_primary,
+        });
+    }
+}
+
+// 球轨迹采样系统：按固定时间间隔记录位置，与帧率无关
+fn update_ball_trail(
+    mut ball_query: Query<(&Transform, &mut Trail), With<Ball>>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    effect_settings: Res<EffectSettings>,
+) {
+    for (transform, mut trail) in ball_query.iter_mut() {
+        // 减少特效模式下不记录轨迹
+        if effect_settings.reduced_effects {
+            trail.positions.clear();
+            continue;
+        }
+
+        trail.sample_timer += time.delta_seconds();
+        if trail.sample_timer < config.trail_interval {
+            continue;
+        }
+        trail.sample_timer %= config.trail_interval;
+
+        // 缓冲区满时丢弃最旧的点
+        if trail.positions.len() >= trail.max_length {
+            trail.positions.pop_front();
+        }
+        trail.positions.push_back(transform.translation);
+    }
+}
+
+// 球轨迹渲染系统：越旧的点越小越透明
+fn render_ball_trail(
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    ball_query: Query<(&Transform, &Ball, &Trail, &Children)>,
+    mut dot_query: Query<(&mut Transform, &mut Sprite, &mut Visibility, &TrailDot), Without<Ball>>,
+) {
+    for (ball_transform, ball, trail, children) in ball_query.iter() {
+        let color = trail_color(ball.velocity.length(), config.ball_speed, &theme);
+        let len = trail.positions.len();
+
+        for &child in children.iter() {
+            let Ok((mut transform, mut sprite, mut visibility, dot)) = dot_query.get_mut(child) else {
+                continue;
+            };
+
+            if dot.index >= len {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+
+            // 子实体坐标相对于球，放在球的后面
+            let position = trail.positions[len - 1 - dot.index];
+            transform.translation = (position - ball_transform.translation).truncate().extend(-0.5);
+
+            let fade = 1.0 - (dot.index + 1) as f32 / (trail.max_length + 1) as f32;
+            let size = BALL_SIZE * (0.4 + 0.6 * fade);
+            sprite.custom_size = Some(Vec2::new(size, size));
+            sprite.color = color.with_a(0.5 * fade);
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+// 轨迹颜色：正常速度为球的颜色，速度越快越偏向主题的高速颜色
+fn trail_color(speed: f32, base_speed: f32, theme: &Theme) -> Color {
+    let t = ((speed - base_speed) / (base_speed * 0.25)).clamp(0.0, 1.0);
+    let colors = theme.colors();
+    lerp_color(colors.ball, colors.ball_fast, t)
+}
+
+// 背景动画系统
+fn animate_background(
+    time: Res<Time>,
+    theme: Res<Theme>,
+    mut bg_query: Query<&mut Sprite, With<Background>>,
+) {
+    let colors = theme.colors();
+    let [base_r, base_g, base_b, _] = colors.background.as_rgba_f32();
+    let variation = theme.background_variation();
+    let time_factor = time.elapsed_seconds() * theme.def().background.speed;
+
+    for mut sprite in bg_query.iter_mut() {
+        let r = base_r + (time_factor.sin() * 0.05 * variation).abs();
+        let g = base_g + (time_factor.cos() * 0.05 * variation).abs();
+        let b = base_b + ((time_factor * 1.5).sin() * 0.1 * variation).abs();
+        sprite.color = Color::rgb(r, g, b);
+    }
+}
+
+// 挡板发光动画系统
+fn animate_paddle_glow(
+    time: Res<Time>,
+    theme: Res<Theme>,
+    paddle_query: Query<(&Transform, &Paddle)>,
+    mut glow_query: Query<(&mut Transform, &mut Sprite, &PaddleGlow), Without<Paddle>>,
+) {
+    for (paddle_transform, paddle) in paddle_query.iter() {
+        for (mut glow_transform, mut glow_sprite, glow) in glow_query.iter_mut() {
+            if glow.player != paddle.player {
+                continue;
+            }
+            // 跟随挡板位置和宽度
+            glow_transform.translation.x = paddle_transform.translation.x;
+            glow_transform.translation.y = paddle_transform.translation.y;
+            glow_sprite.custom_size = Some(Vec2::new(paddle.width, PADDLE_HEIGHT) + theme.sizes().paddle_glow);
+            
+            // 脉冲发光效果
+            let pulse = (time.elapsed_seconds() * 3.0).sin() * 0.1 + 0.3;
+            glow_sprite.color.set_a(pulse);
+            
+            // 大小变化
+            let scale = 1.0 + (time.elapsed_seconds() * 2.0).sin() * 0.1;
+            glow_transform.scale = Vec3::new(scale, scale, 1.0);
+        }
+    }
+}
+
+// 显示游戏结束模态框（简化版本）
+fn show_game_over_modal(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    scoring: Res<Scoring>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    coop: Res<Coop>,
+    endless: Res<Endless>,
+    high_scores: Res<HighScores>,
+    modal_query: Query<Entity, With<GameOverModal>>,
+    mut modal_text_query: Query<&mut Text, With<ModalText>>,
+    mut backdrop_query: Query<&mut Sprite, With<ModalBackground>>,
+) {
+    let colors = theme.colors();
+    let modal_color = if game_state.won { colors.text_good } else { colors.text_bad };
+
+    // 切换语言或主题、记录最高分后更新已显示的模态框
+    if locale.is_changed() || theme.is_changed() || high_scores.is_changed() {
+        for mut text in modal_text_query.iter_mut() {
+            text.sections[0].value = game_over_message(&game_state, &scoring, &coop, &endless, &high_scores, &locale);
+            text.sections[0].style.color = modal_color;
+            text.sections[0].style.font_size = theme.fonts().modal;
+        }
+        for mut sprite in backdrop_query.iter_mut() {
+            sprite.color = colors.backdrop;
+        }
+    }
+
+    // 如果游戏结束且还没有显示模态框
+    if (game_state.game_over || game_state.won) && modal_query.is_empty() {
+        // 创建简单的游戏结束弹框
+        commands.spawn((centered_row(33.0), GameOverModal)).with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    game_over_message(&game_state, &scoring, &coop, &endless, &high_scores, &locale),
+                    TextStyle {
+                        font: Default::default(),
+                        font_size: theme.fonts().modal,
+                        color: modal_color,
+                    },
+                ).with_text_alignment(TextAlignment::Center),
+                ModalText,
+            ));
+        });
+        
+        // 创建半透明背景
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: colors.backdrop,
+                    custom_size: Some(Vec2::new(ARENA_WIDTH, ARENA_HEIGHT)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, 5.0), // 在游戏元素之上
+                ..default()
+            },
+            ModalBackground,
+        ));
+    }
+    
+    // 如果游戏重新开始，移除模态框
+    if !game_state.game_over && !game_state.won {
+        for entity in modal_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn game_over_message(
+    game_state: &GameState,
+    scoring: &Scoring,
+    coop: &Coop,
+    endless: &Endless,
+    high_scores: &HighScores,
+    locale: &Locale,
+) -> String {
+    let message = if endless.active {
+        endless::game_over_message(game_state, scoring, locale)
+    } else if game_state.won {
+        // 通关界面显示得分明细
+        let breakdown = scoring.breakdown;
+        locale.format("modal.victory", &[
+            ("bricks", &breakdown.bricks),
+            ("combo", &breakdown.combo_bonus),
+            ("rows", &breakdown.row_bonus),
+            ("time", &breakdown.time_bonus),
+            ("lives", &breakdown.lives_bonus),
+            ("score", &game_state.score),
+        ])
+    } else {
+        locale.format("modal.game_over", &[("score", &game_state.score)])
+    };
+    // 双人时附上各玩家的得分，最后是最高分
+    message + &coop::scores_line(coop, game_state, locale) + &highscores::summary_line(high_scores, endless, coop, locale)
+}
\ No newline at end of file
@@ -0,0 +1,148 @@
+// 本地化：字符串表放在 assets/locales 下，编译时嵌入
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use bevy::prelude::*;
+
+// 基准语言，其他语言缺少的键回退到这里
+const FALLBACK: Language = Language::Chinese;
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Locale>().add_system(update_window_title);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Language {
+    Chinese,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Chinese => "zh-CN",
+            Language::English => "en",
+        }
+    }
+
+    // 语言名称总是用该语言本身显示
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::Chinese => "中文",
+            Language::English => "English",
+        }
+    }
+
+    // Bevy 自带的默认字体没有这些语言的字形
+    pub fn needs_cjk_font(self) -> bool {
+        matches!(self, Language::Chinese)
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::Chinese => include_str!("../assets/locales/zh-CN.ron"),
+            Language::English => include_str!("../assets/locales/en.ron"),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&language| language == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+fn parse_table(language: Language) -> HashMap<String, String> {
+    ron::from_str(language.source()).unwrap_or_else(|err| {
+        panic!("语言文件 {}.ron 格式错误: {}", language.code(), err)
+    })
+}
+
+#[derive(Resource)]
+pub struct Locale {
+    pub language: Language,
+    tables: HashMap<Language, HashMap<String, String>>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            language: FALLBACK,
+            tables: Language::ALL.iter().map(|&language| (language, parse_table(language))).collect(),
+        }
+    }
+}
+
+impl Locale {
+    // 查找顺序：当前语言 -> 基准语言 -> 键名本身
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        [self.language, FALLBACK]
+            .iter()
+            .find_map(|language| self.tables[language].get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    // 任何语言都没有这个键时返回给定的默认文字
+    pub fn get_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        [self.language, FALLBACK]
+            .iter()
+            .find_map(|language| self.tables[language].get(key))
+            .map(String::as_str)
+            .unwrap_or(default)
+    }
+
+    // 替换字符串中的 {name} 占位符
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+}
+
+// 列出各语言相对基准语言缺少的键
+pub fn missing_keys() -> Vec<(Language, String)> {
+    let base = parse_table(FALLBACK);
+    let mut missing = Vec::new();
+    for language in Language::ALL {
+        if language == FALLBACK {
+            continue;
+        }
+        let table = parse_table(language);
+        let mut keys: Vec<_> = base.keys().filter(|key| !table.contains_key(*key)).cloned().collect();
+        keys.sort();
+        missing.extend(keys.into_iter().map(|key| (language, key)));
+    }
+    missing
+}
+
+// 供 CI 使用：报告未翻译的键，有缺失时返回 false
+pub fn check_locales() -> bool {
+    let missing = missing_keys();
+    for (language, key) in &missing {
+        println!("[{}] 缺少翻译: {}", language.code(), key);
+    }
+    if missing.is_empty() {
+        println!("所有语言文件完整");
+    }
+    missing.is_empty()
+}
+
+fn update_window_title(
+    locale: Res<Locale>,
+    mut window_query: Query<&mut Window, With<bevy::window::PrimaryWindow>>,
+) {
+    if !locale.is_changed() {
+        return;
+    }
+    for mut window in window_query.iter_mut() {
+        window.title = locale.get("window.title").to_string();
+    }
+}
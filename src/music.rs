@@ -0,0 +1,246 @@
+// 自适应背景音乐：几条等长的分轨同时循环播放，按游戏强度淡入淡出
+use bevy::prelude::*;
+
+use crate::audio::{mix_into, note_freq, tone, AudioSettings, SynthSound, Wave, SAMPLE_RATE};
+use crate::settings::SettingsMenu;
+use crate::config::GameConfig;
+use crate::{handle_restart, Ball, Brick, GameState};
+
+const BEAT: f32 = 0.5;                // 120 BPM
+const BARS: [i32; 4] = [-24, -28, -21, -26];  // 每小节的根音：A2 F2 E3 G2
+const FADE_SPEED: f32 = 1.5;          // 每秒音量变化量
+const LAYER_RAMP: f32 = 0.15;         // 强度超过门限后多少范围内完全淡入
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicIntensity>()
+            .add_startup_system(compose_stems)
+            .add_system(start_stems)
+            .add_system(update_music_intensity.before(handle_restart))
+            .add_system(mix_stems.after(update_music_intensity).after(start_stems));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Stem {
+    Pad,    // 和弦铺底，任何时候都可以响
+    Bass,
+    Drums,
+    Arp,
+    Lead,
+}
+
+impl Stem {
+    const ALL: [Stem; 5] = [Stem::Pad, Stem::Bass, Stem::Drums, Stem::Arp, Stem::Lead];
+
+    // 进行中时这一层开始淡入的强度
+    fn threshold(self) -> f32 {
+        match self {
+            Stem::Pad | Stem::Bass => 0.0,
+            Stem::Drums => 0.2,
+            Stem::Arp => 0.45,
+            Stem::Lead => 0.7,
+        }
+    }
+
+    fn target_level(self, mood: MusicMood, intensity: f32) -> f32 {
+        match mood {
+            MusicMood::Playing => ((intensity - self.threshold()) / LAYER_RAMP).clamp(0.0, 1.0),
+            // 暂停时只留下和弦
+            MusicMood::Paused => if self == Stem::Pad { 0.6 } else { 0.0 },
+            MusicMood::Victory => if matches!(self, Stem::Pad | Stem::Arp) { 1.0 } else { 0.0 },
+            MusicMood::GameOver => if self == Stem::Pad { 0.4 } else { 0.0 },
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MusicMood {
+    Playing,
+    Paused,
+    Victory,
+    GameOver,
+}
+
+// 音乐强度 0 到 1，由砖块剩余比例、球速和球数决定
+#[derive(Resource)]
+pub struct MusicIntensity {
+    pub value: f32,
+    pub mood: MusicMood,
+    peak_bricks: usize,  // 本局开始时的砖块数，用来算清除比例
+}
+
+impl Default for MusicIntensity {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            mood: MusicMood::Playing,
+            peak_bricks: 0,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct StemSources(Vec<(Stem, Handle<SynthSound>)>);
+
+// 正在播放的分轨和各自当前的音量
+#[derive(Resource)]
+struct PlayingStems(Vec<(Stem, Handle<AudioSink>, f32)>);
+
+fn stem_length() -> usize {
+    (BARS.len() as f32 * 4.0 * BEAT * SAMPLE_RATE as f32) as usize
+}
+
+// 每条分轨都补齐到同样长度，循环时才能保持对齐
+fn compose(stem: Stem) -> Vec<f32> {
+    let mut track = vec![0.0; stem_length()];
+
+    for (bar, &root) in BARS.iter().enumerate() {
+        let bar_start = bar as f32 * 4.0 * BEAT;
+        match stem {
+            Stem::Pad => {
+                for interval in [12, 15, 19] {
+                    let freq = note_freq(root + interval);
+                    mix_into(&mut track, &tone(Wave::Sine, freq, freq, 4.0 * BEAT, 0.5, 0.08), bar_start);
+                }
+            }
+            Stem::Bass => {
+                for eighth in 0..8 {
+                    let offset = bar_start + eighth as f32 * BEAT / 2.0;
+                    let freq = note_freq(root);
+                    mix_into(&mut track, &tone(Wave::Triangle, freq, freq, BEAT / 2.0, 6.0, 0.25), offset);
+                }
+            }
+            Stem::Drums => {
+                for beat in 0..4 {
+                    let offset = bar_start + beat as f32 * BEAT;
+                    mix_into(&mut track, &tone(Wave::Sine, 150.0, 45.0, 0.15, 18.0, 0.5), offset);
+                    mix_into(&mut track, &tone(Wave::Noise, 0.0, 0.0, 0.05, 60.0, 0.12), offset + BEAT / 2.0);
+                }
+            }
+            Stem::Arp => {
+                for eighth in 0..8 {
+                    let offset = bar_start + eighth as f32 * BEAT / 2.0;
+                    let freq = note_freq(root + [12, 15, 19, 24][eighth % 4]);
+                    mix_into(&mut track, &tone(Wave::Square, freq, freq, BEAT / 4.0, 12.0, 0.05), offset);
+                }
+            }
+            Stem::Lead => {
+                let melody = [[24, 27, 31, 27], [24, 27, 29, 27], [26, 29, 31, 34], [26, 24, 22, 19]][bar];
+                for (beat, interval) in melody.into_iter().enumerate() {
+                    let offset = bar_start + beat as f32 * BEAT;
+                    let freq = note_freq(root + interval);
+                    mix_into(&mut track, &tone(Wave::Triangle, freq, freq, BEAT * 0.9, 2.0, 0.12), offset);
+                }
+            }
+        }
+    }
+
+    track.truncate(stem_length());
+    track
+}
+
+fn compose_stems(mut commands: Commands, mut sounds: ResMut<Assets<SynthSound>>) {
+    let sources = Stem::ALL
+        .iter()
+        .map(|&stem| (stem, sounds.add(SynthSound { samples: compose(stem).into() })))
+        .collect();
+    commands.insert_resource(StemSources(sources));
+}
+
+// 所有分轨在同一帧以静音开始播放，之后只调整音量
+fn start_stems(
+    mut commands: Commands,
+    sources: Option<Res<StemSources>>,
+    playing: Option<Res<PlayingStems>>,
+    audio: Res<Audio<SynthSound>>,
+    sinks: Res<Assets<AudioSink>>,
+) {
+    let Some(sources) = sources else { return };
+    if playing.is_some() {
+        return;
+    }
+
+    let stems = sources
+        .0
+        .iter()
+        .map(|(stem, source)| {
+            let weak = audio.play_with_settings(source.clone(), PlaybackSettings::LOOP.with_volume(0.0));
+            (*stem, sinks.get_handle(weak), 0.0)
+        })
+        .collect();
+    commands.insert_resource(PlayingStems(stems));
+}
+
+fn update_music_intensity(
+    game_state: Res<GameState>,
+    config: Res<GameConfig>,
+    menu: Res<SettingsMenu>,
+    ball_query: Query<&Ball>,
+    brick_query: Query<&Brick>,
+    mut intensity: ResMut<MusicIntensity>,
+) {
+    // 重新开始或换关卡时清零，旧砖块要到这一帧结束才移除，下一帧再按新关卡重新记录
+    if game_state.restart_requested {
+        intensity.peak_bricks = 0;
+        return;
+    }
+
+    // 记录这一局中最多的砖块数；不可击碎的砖块不算
+    let bricks = brick_query.iter().filter(|brick| !brick.indestructible).count();
+    if bricks > intensity.peak_bricks {
+        intensity.peak_bricks = bricks;
+    }
+    let cleared = if intensity.peak_bricks > 0 {
+        1.0 - bricks as f32 / intensity.peak_bricks as f32
+    } else {
+        0.0
+    };
+
+    let balls = ball_query.iter().count();
+    let top_speed = ball_query.iter().map(|ball| ball.velocity.length()).fold(0.0, f32::max);
+    let speed = ((top_speed / config.ball_speed - 1.0) / 0.5).clamp(0.0, 1.0);
+    let multi_ball = (balls.saturating_sub(1) as f32 / config.max_balls.saturating_sub(1).max(1) as f32).clamp(0.0, 1.0);
+
+    let value = (0.5 * cleared + 0.3 * speed + 0.4 * multi_ball).min(1.0);
+    let mood = if game_state.won {
+        MusicMood::Victory
+    } else if game_state.game_over {
+        MusicMood::GameOver
+    } else if menu.open {
+        MusicMood::Paused
+    } else {
+        MusicMood::Playing
+    };
+
+    // 只在确实变化时写入，避免每帧触发变更检测
+    if (intensity.value - value).abs() > f32::EPSILON {
+        intensity.value = value;
+    }
+    if intensity.mood != mood {
+        intensity.mood = mood;
+    }
+}
+
+// 各层音量平滑地移向目标值
+fn mix_stems(
+    time: Res<Time>,
+    settings: Res<AudioSettings>,
+    intensity: Res<MusicIntensity>,
+    playing: Option<ResMut<PlayingStems>>,
+    sinks: Res<Assets<AudioSink>>,
+) {
+    let Some(mut playing) = playing else { return };
+    let step = FADE_SPEED * time.delta_seconds();
+    let volume = settings.master * settings.music;
+
+    for (stem, sink, level) in playing.0.iter_mut() {
+        let target = stem.target_level(intensity.mood, intensity.value);
+        *level += (target - *level).clamp(-step, step);
+        if let Some(sink) = sinks.get(sink) {
+            sink.set_volume(volume * *level);
+        }
+    }
+}
@@ -0,0 +1,853 @@
+// 联机：两台机器通过 UDP 点对点对战或合作，每台机器都跑完整的确定性模拟，只交换输入
+// 本地输入延迟几帧再生效，对方的输入还没到时先按他上一帧的输入预测；真实输入和预测不同时
+// 回到那一帧的快照重新模拟（回滚），所以延迟只在预测错时才会表现为画面跳动
+// 双方每 CHECKSUM_INTERVAL 帧交换一次局面校验和，不一致说明模拟出现了分歧（不同步）
+// --net-test 不开窗口，用随机输入跑固定帧数，可以模拟延迟、抖动和丢包，CI 用它在同一台机器上让两个进程互相连接
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::GameConfig;
+use crate::coop::{Coop, CoopLayout};
+use crate::level::Level;
+use crate::locale::Locale;
+use crate::sim::{SimState, Simulation, STEP};
+use crate::versus::{arena_app, Arena, ArenaViewSet, Versus, PLAYERS};
+use crate::{GameState, PlayerInput};
+
+const MAGIC: &[u8; 4] = b"BBNT";
+const VERSION: u8 = 1;
+const DEFAULT_DELAY: u32 = 2;                          // 本地输入延迟的帧数
+const MAX_PREDICTION: u32 = 8;                         // 最多领先对方已确认输入的帧数，超过时等待
+const CHECKSUM_INTERVAL: u32 = 60;                     // 每隔多少帧比较一次校验和
+const MAX_INPUTS_PER_PACKET: usize = 64;
+const JOIN_INTERVAL: Duration = Duration::from_millis(100);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);  // 这么久收不到对方的消息就认为断开
+const WAITING_TICKS: u32 = 30;                         // 连续等待这么多帧才提示“等待对方”
+const MAX_FRAME_TIME: f32 = 0.25;
+const TEST_FRAMES: u32 = 1800;
+const LINGER: Duration = Duration::from_secs(1);       // 测试结束后继续收发一会儿，让对方也能完成校验
+
+// 可以回滚的游戏：保存和恢复快照，按双方输入推进一帧，计算局面的校验和
+pub(crate) trait RollbackGame {
+    type State: Clone;
+
+    fn save(&mut self) -> Self::State;
+    fn load(&mut self, state: &Self::State);
+    fn advance(&mut self, directions: [f32; PLAYERS]);
+    fn checksum(&mut self) -> u64;
+}
+
+// 联机合作：一块场地，两块挡板并排
+impl RollbackGame for Simulation {
+    type State = SimState;
+
+    fn save(&mut self) -> SimState {
+        Simulation::save(self)
+    }
+
+    fn load(&mut self, state: &SimState) {
+        Simulation::load(self, state);
+    }
+
+    fn advance(&mut self, directions: [f32; PLAYERS]) {
+        self.world.insert_resource(PlayerInput { direction: directions, ..default() });
+        self.step();
+    }
+
+    fn checksum(&mut self) -> u64 {
+        Simulation::checksum(self)
+    }
+}
+
+impl Arena for Simulation {
+    fn fields(&self) -> Vec<&World> {
+        vec![&self.world]
+    }
+
+    fn hud(&self, _field: usize, locale: &Locale) -> String {
+        let state = self.world.resource::<GameState>();
+        let coop = self.world.resource::<Coop>();
+        let players: Vec<String> = (0..coop.players)
+            .map(|player| locale.format("coop.player", &[("player", &(player + 1)), ("score", &state.player_scores[player])]))
+            .collect();
+        let summary = locale.format("net.coop_hud", &[("score", &state.score), ("lives", &state.lives)]);
+        format!("{}   {}", summary, players.join("   "))
+    }
+
+    fn outcome(&self, locale: &Locale) -> Option<String> {
+        let state = self.world.resource::<GameState>();
+        if state.won {
+            Some(locale.get("net.cleared").to_string())
+        } else if state.game_over {
+            Some(locale.get("net.failed").to_string())
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NetMode {
+    Versus,
+    Coop,
+}
+
+// 主机在握手时告诉加入方的开局信息，关卡和配置由双方各自读取，需要一致
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Hello {
+    seed: u64,
+    mode: NetMode,
+}
+
+// 消息格式：MAGIC、版本、类型，之后是小端序的字段
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Packet {
+    Join,
+    Hello(Hello),
+    Input {
+        ack: u32,                      // 发送方已经连续收到的对方输入帧数
+        start: u32,                    // inputs 中第一个输入的帧号
+        inputs: Vec<i8>,               // 从对方还没确认的那一帧起的全部输入，丢包时下一个消息会补上
+        checksum: Option<(u32, u64)>,  // 发送方最近一个已确定的校验和
+    },
+}
+
+impl Packet {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        match self {
+            Packet::Join => bytes.push(0),
+            Packet::Hello(hello) => {
+                bytes.push(1);
+                bytes.extend(hello.seed.to_le_bytes());
+                bytes.push(match hello.mode {
+                    NetMode::Versus => 0,
+                    NetMode::Coop => 1,
+                });
+            }
+            Packet::Input { ack, start, inputs, checksum } => {
+                bytes.push(2);
+                bytes.extend(ack.to_le_bytes());
+                bytes.extend(start.to_le_bytes());
+                bytes.extend((inputs.len() as u16).to_le_bytes());
+                bytes.extend(inputs.iter().map(|&input| input as u8));
+                let (frame, value) = checksum.unwrap_or((u32::MAX, 0));
+                bytes.extend(frame.to_le_bytes());
+                bytes.extend(value.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    // 格式不对或版本不同的消息直接丢弃
+    fn decode(bytes: &[u8]) -> Option<Packet> {
+        let mut reader = Reader(bytes);
+        if reader.take(4)? != MAGIC || reader.u8()? != VERSION {
+            return None;
+        }
+        match reader.u8()? {
+            0 => Some(Packet::Join),
+            1 => {
+                let seed = reader.u64()?;
+                let mode = match reader.u8()? {
+                    0 => NetMode::Versus,
+                    1 => NetMode::Coop,
+                    _ => return None,
+                };
+                Some(Packet::Hello(Hello { seed, mode }))
+            }
+            2 => {
+                let ack = reader.u32()?;
+                let start = reader.u32()?;
+                let count = u16::from_le_bytes(reader.take(2)?.try_into().ok()?) as usize;
+                let inputs = reader.take(count)?.iter().map(|&byte| byte as i8).collect();
+                let frame = reader.u32()?;
+                let value = reader.u64()?;
+                let checksum = (frame != u32::MAX).then_some((frame, value));
+                Some(Packet::Input { ack, start, inputs, checksum })
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Option<&'a [u8]> {
+        if self.0.len() < count {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+// 方向量化成一个字节传输，本地的输入也按量化后的值模拟，双方算出的结果才会一样
+fn quantize(direction: f32) -> i8 {
+    (direction.clamp(-1.0, 1.0) * 127.0).round() as i8
+}
+
+fn dequantize(input: i8) -> f32 {
+    input as f32 / 127.0
+}
+
+// 收发原始消息，不保证送达和顺序
+pub(crate) trait Transport {
+    fn send(&mut self, bytes: &[u8]);
+    fn recv(&mut self) -> Option<Vec<u8>>;
+}
+
+// 模拟的网络状况，只作用于发出的消息；两端都设置时往返延迟是两倍
+#[derive(Clone, Copy, Default, Debug)]
+pub struct LinkConditions {
+    pub latency: Duration,
+    pub jitter: Duration,  // 每个消息额外随机延迟 0 到 jitter，可能因此乱序
+    pub loss: f64,         // 丢包概率
+}
+
+pub(crate) struct UdpTransport {
+    socket: UdpSocket,
+    peer: SocketAddr,
+    conditions: LinkConditions,
+    outbox: Vec<(Instant, Vec<u8>)>,  // 模拟延迟时还没到发送时间的消息
+    rng: StdRng,
+}
+
+impl UdpTransport {
+    pub(crate) fn bind(local: SocketAddr, peer: SocketAddr, conditions: LinkConditions) -> io::Result<Self> {
+        let socket = UdpSocket::bind(local)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            peer,
+            conditions,
+            outbox: Vec::new(),
+            rng: StdRng::seed_from_u64(local.port() as u64),
+        })
+    }
+
+    fn flush(&mut self) {
+        let now = Instant::now();
+        let (socket, peer) = (&self.socket, self.peer);
+        self.outbox.retain(|(due, bytes)| {
+            if *due > now {
+                return true;
+            }
+            let _ = socket.send_to(bytes, peer);
+            false
+        });
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&mut self, bytes: &[u8]) {
+        if self.conditions.loss > 0.0 && self.rng.gen_bool(self.conditions.loss.min(1.0)) {
+            return;
+        }
+        let delay = self.conditions.latency + self.conditions.jitter.mul_f32(self.rng.gen());
+        if delay.is_zero() {
+            let _ = self.socket.send_to(bytes, self.peer);
+        } else {
+            self.outbox.push((Instant::now() + delay, bytes.to_vec()));
+        }
+        self.flush();
+    }
+
+    // 只接收来自对方地址的消息；对方还没启动时系统可能报告端口不可达，当作没有消息
+    fn recv(&mut self) -> Option<Vec<u8>> {
+        self.flush();
+        let mut buffer = [0; 1024];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, from)) if from == self.peer => return Some(buffer[..len].to_vec()),
+                Ok(_) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+// 握手：加入方不断发送 Join，主机收到后回复 Hello，双方各自开始
+// 主机的 Hello 丢失时加入方会继续发 Join，由 Session 再回复
+fn connect<T: Transport>(transport: &mut T, host: Option<Hello>) -> io::Result<Hello> {
+    let deadline = Instant::now() + CONNECT_TIMEOUT;
+    let mut last_join = None::<Instant>;
+    while Instant::now() < deadline {
+        if host.is_none() && last_join.is_none_or(|sent| sent.elapsed() >= JOIN_INTERVAL) {
+            transport.send(&Packet::Join.encode());
+            last_join = Some(Instant::now());
+        }
+        while let Some(bytes) = transport.recv() {
+            match (host, Packet::decode(&bytes)) {
+                (Some(hello), Some(Packet::Join)) => {
+                    transport.send(&Packet::Hello(hello).encode());
+                    return Ok(hello);
+                }
+                (None, Some(Packet::Hello(hello))) => return Ok(hello),
+                _ => {}
+            }
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    Err(io::Error::new(io::ErrorKind::TimedOut, "对方没有响应"))
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct NetStats {
+    pub rollbacks: u32,
+    pub resimulated: u32,    // 回滚时重新模拟的总帧数
+    pub max_rollback: u32,
+    pub stalls: u32,         // 因为领先太多而等待的次数
+    pub checksums: u32,      // 已经比较过的校验和个数
+}
+
+// 一局联机的输入交换和回滚，每个模拟帧调用一次 tick
+pub(crate) struct Session<G: RollbackGame, T: Transport> {
+    pub game: G,
+    transport: T,
+    local: usize,                        // 本机玩家的编号，主机为 0
+    hello: Option<Vec<u8>>,              // 主机的握手回复
+    frame: u32,                          // 下一个要模拟的帧
+    local_inputs: Vec<i8>,               // 本机每一帧的输入，前 delay 帧为 0
+    remote_inputs: Vec<i8>,              // 已收到的对方输入，总是从第 0 帧起连续
+    used_remote: Vec<i8>,                // 模拟每一帧时用的对方输入（真实或预测）
+    states: VecDeque<(u32, G::State)>,   // 每一帧开始时的快照，只保留还可能回滚到的
+    local_checksums: BTreeMap<u32, u64>,
+    remote_checksums: BTreeMap<u32, u64>,
+    remote_ack: u32,                     // 对方已经连续收到的本机输入帧数
+    last_heard: Instant,
+    waiting: u32,                        // 连续等待对方的帧数
+    pub verified: u32,                   // 最近一次双方一致的校验和所在帧
+    pub desync: Option<u32>,             // 第一次发现校验和不一致的帧
+    pub stats: NetStats,
+}
+
+impl<G: RollbackGame, T: Transport> Session<G, T> {
+    fn new(game: G, transport: T, local: usize, delay: u32, hello: Option<Hello>) -> Self {
+        Self {
+            game,
+            transport,
+            local,
+            hello: hello.map(|hello| Packet::Hello(hello).encode()),
+            frame: 0,
+            local_inputs: vec![0; delay as usize],
+            remote_inputs: Vec::new(),
+            used_remote: Vec::new(),
+            states: VecDeque::new(),
+            local_checksums: BTreeMap::new(),
+            remote_checksums: BTreeMap::new(),
+            remote_ack: 0,
+            last_heard: Instant::now(),
+            waiting: 0,
+            verified: 0,
+            desync: None,
+            stats: NetStats::default(),
+        }
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    // 已经收到对方输入的帧数，这之前的帧不会再回滚
+    fn confirmed(&self) -> u32 {
+        self.remote_inputs.len() as u32
+    }
+
+    pub fn disconnected(&self) -> bool {
+        self.last_heard.elapsed() > PEER_TIMEOUT
+    }
+
+    pub fn waiting(&self) -> bool {
+        self.waiting >= WAITING_TICKS
+    }
+
+    // 收消息、必要时回滚，然后推进一帧；领先对方太多时这一帧只收发不推进，返回 false
+    pub fn tick(&mut self, direction: f32) -> bool {
+        self.receive();
+        let advance = self.frame < self.confirmed() + MAX_PREDICTION;
+        if advance {
+            self.waiting = 0;
+            self.local_inputs.push(quantize(direction));
+            self.simulate_frame();
+        } else {
+            self.waiting += 1;
+            self.stats.stalls += 1;
+        }
+        self.send();
+        advance
+    }
+
+    // 只收发消息不推进
+    pub fn poll(&mut self) {
+        self.receive();
+        self.send();
+    }
+
+    fn receive(&mut self) {
+        let previous = self.confirmed();
+        let mut rollback_from = None;
+        while let Some(bytes) = self.transport.recv() {
+            let Some(packet) = Packet::decode(&bytes) else { continue };
+            self.last_heard = Instant::now();
+            match packet {
+                Packet::Join => {
+                    if let Some(hello) = &self.hello {
+                        self.transport.send(hello);
+                    }
+                }
+                Packet::Hello(_) => {}
+                Packet::Input { ack, start, inputs, checksum } => {
+                    self.remote_ack = self.remote_ack.max(ack);
+                    // 对方会重复发送同一个校验和，已经比较过的帧不再记录
+                    if let Some((frame, value)) = checksum.filter(|(frame, _)| *frame > self.verified) {
+                        self.remote_checksums.insert(frame, value);
+                    }
+                    // 对方总是从我们确认过的帧开始发，乱序到达的旧消息可能和已有的重叠
+                    let known = self.remote_inputs.len();
+                    let Some(skip) = known.checked_sub(start as usize) else { continue };
+                    for &input in inputs.iter().skip(skip) {
+                        let frame = self.remote_inputs.len();
+                        if rollback_from.is_none() && self.used_remote.get(frame).is_some_and(|&used| used != input) {
+                            rollback_from = Some(frame as u32);
+                        }
+                        self.remote_inputs.push(input);
+                    }
+                }
+            }
+        }
+
+        // 之前已经确认的帧不会再回滚，它们的快照可以丢掉
+        while self.states.front().is_some_and(|(frame, _)| *frame < previous) {
+            self.states.pop_front();
+        }
+        if let Some(from) = rollback_from {
+            self.rollback(from);
+        }
+        self.compare_checksums();
+    }
+
+    // 回到 from 帧开始时的快照，用新收到的输入重新模拟到当前帧
+    fn rollback(&mut self, from: u32) {
+        let target = self.frame;
+        let index = self.states.iter().position(|(frame, _)| *frame == from).expect("回滚的帧没有快照");
+        self.game.load(&self.states[index].1);
+        self.states.truncate(index);
+        self.used_remote.truncate(from as usize);
+        self.frame = from;
+        while self.frame < target {
+            self.simulate_frame();
+        }
+
+        self.stats.rollbacks += 1;
+        self.stats.resimulated += target - from;
+        self.stats.max_rollback = self.stats.max_rollback.max(target - from);
+    }
+
+    fn simulate_frame(&mut self) {
+        let frame = self.frame as usize;
+        self.states.push_back((self.frame, self.game.save()));
+        // 还没收到的对方输入按他最后一个已知输入预测
+        let remote = self.remote_inputs.get(frame).or(self.remote_inputs.last()).copied().unwrap_or(0);
+        self.used_remote.push(remote);
+
+        let mut directions = [0.0; PLAYERS];
+        directions[self.local] = dequantize(self.local_inputs[frame]);
+        directions[1 - self.local] = dequantize(remote);
+        self.game.advance(directions);
+        self.frame += 1;
+        if self.frame.is_multiple_of(CHECKSUM_INTERVAL) {
+            self.local_checksums.insert(self.frame, self.game.checksum());
+        }
+    }
+
+    // 某一帧之前的输入都已确认时，它的校验和就不会再变
+    fn settled(&self) -> u32 {
+        self.confirmed().min(self.frame)
+    }
+
+    fn compare_checksums(&mut self) {
+        let settled = self.settled();
+        let ready: Vec<u32> = self.remote_checksums.range(..=settled).map(|(frame, _)| *frame).collect();
+        for frame in ready {
+            let remote = self.remote_checksums.remove(&frame);
+            let Some(local) = self.local_checksums.get(&frame) else { continue };
+            self.stats.checksums += 1;
+            if Some(*local) == remote {
+                self.verified = self.verified.max(frame);
+            } else if self.desync.is_none() {
+                warn!("第 {} 帧校验和不一致，双方已不同步", frame);
+                self.desync = Some(frame);
+            }
+        }
+        let verified = self.verified;
+        self.local_checksums.retain(|frame, _| *frame >= verified);
+    }
+
+    fn send(&mut self) {
+        let settled = self.settled();
+        let checksum = self.local_checksums.range(..=settled).next_back().map(|(frame, value)| (*frame, *value));
+        let start = (self.remote_ack as usize).min(self.local_inputs.len());
+        let end = (start + MAX_INPUTS_PER_PACKET).min(self.local_inputs.len());
+        let packet = Packet::Input {
+            ack: self.confirmed(),
+            start: start as u32,
+            inputs: self.local_inputs[start..end].to_vec(),
+            checksum,
+        };
+        self.transport.send(&packet.encode());
+    }
+}
+
+// 命令行参数：--net host|join 本机地址 对方地址，或 --net-test 加同样的参数
+pub struct NetOptions {
+    host: bool,
+    local: SocketAddr,
+    peer: SocketAddr,
+    mode: NetMode,       // 只有主机的设置生效
+    delay: u32,
+    frames: u32,         // --net-test 模拟的帧数
+    conditions: LinkConditions,
+}
+
+impl NetOptions {
+    // 没有联机参数时返回 None，参数不对时打印用法并退出
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let index = args.iter().position(|arg| arg == "--net" || arg == "--net-test")?;
+        let usage = || -> ! {
+            eprintln!(" 用法：{} host|join 本机地址 对方地址 [--coop] [--delay 帧数]", args[index]);
+            eprintln!("       测试时可加 [--frames 帧数] [--latency 毫秒] [--jitter 毫秒] [--loss 丢包率]");
+            std::process::exit(2);
+        };
+        let host = match args.get(index + 1).map(String::as_str) {
+            Some("host") => true,
+            Some("join") => false,
+            _ => usage(),
+        };
+        let address = |offset: usize| {
+            args.get(index + offset)
+                .and_then(|value| value.to_socket_addrs().ok())
+                .and_then(|mut addresses| addresses.next())
+                .unwrap_or_else(|| usage())
+        };
+        let number = |key: &str, default: f64| match args.iter().position(|arg| arg == key) {
+            Some(position) => args.get(position + 1).and_then(|value| value.parse().ok()).unwrap_or_else(|| usage()),
+            None => default,
+        };
+        let millis = |key: &str| Duration::from_secs_f64(number(key, 0.0).max(0.0) / 1000.0);
+
+        Some(Self {
+            host,
+            local: address(2),
+            peer: address(3),
+            mode: if args.iter().any(|arg| arg == "--coop") { NetMode::Coop } else { NetMode::Versus },
+            delay: number("--delay", DEFAULT_DELAY as f64) as u32,
+            frames: number("--frames", TEST_FRAMES as f64) as u32,
+            conditions: LinkConditions {
+                latency: millis("--latency"),
+                jitter: millis("--jitter"),
+                loss: number("--loss", 0.0).clamp(0.0, 1.0),
+            },
+        })
+    }
+
+    // 绑定端口并完成握手，返回本机玩家编号和开局信息
+    fn connect(&self) -> io::Result<(UdpTransport, usize, Hello)> {
+        let mut transport = UdpTransport::bind(self.local, self.peer, self.conditions)?;
+        println!(" {} 连接 {} ← → {}", if self.host { "主机" } else { "加入" }, self.local, self.peer);
+        let host = self.host.then(|| Hello { seed: rand::random(), mode: self.mode });
+        let hello = connect(&mut transport, host)?;
+        println!(" 已连接，模式 {:?}，种子 {}", hello.mode, hello.seed);
+        Ok((transport, if self.host { 0 } else { 1 }, hello))
+    }
+}
+
+fn coop_game(config: GameConfig, level: Level, seed: u64) -> Simulation {
+    Simulation::with_coop(config, level, seed, Coop::two_players(CoopLayout::SideBySide))
+}
+
+// 联机时的窗口资源：会话和还没模拟的时间
+#[derive(Resource)]
+struct NetPlay<G: RollbackGame> {
+    session: Session<G, UdpTransport>,
+    accumulator: f32,
+}
+
+impl<G: RollbackGame + Arena> Arena for NetPlay<G> {
+    fn fields(&self) -> Vec<&World> {
+        self.session.game.fields()
+    }
+
+    fn hud(&self, field: usize, locale: &Locale) -> String {
+        self.session.game.hud(field, locale)
+    }
+
+    fn outcome(&self, locale: &Locale) -> Option<String> {
+        self.session.game.outcome(locale)
+    }
+
+    // 联机时不能单方面重开，只提示退出；连接问题优先显示
+    fn banner(&self, locale: &Locale) -> String {
+        if let Some(frame) = self.session.desync {
+            locale.format("net.desync", &[("frame", &frame)])
+        } else if self.session.disconnected() {
+            locale.get("net.disconnected").to_string()
+        } else if let Some(outcome) = self.outcome(locale) {
+            format!("{}\n{}", outcome, locale.get("net.quit"))
+        } else if self.session.waiting() {
+            locale.get("net.waiting").to_string()
+        } else {
+            String::new()
+        }
+    }
+}
+
+pub fn run(options: NetOptions, level: Level, config: GameConfig) {
+    let (transport, local, hello) = options.connect().unwrap_or_else(|err| {
+        eprintln!(" 联机失败：{}", err);
+        std::process::exit(1);
+    });
+    let host = options.host.then_some(hello);
+    match hello.mode {
+        NetMode::Versus => {
+            let game = Versus::new(config, level, hello.seed);
+            run_window(Session::new(game, transport, local, options.delay, host));
+        }
+        NetMode::Coop => {
+            let game = coop_game(config, level, hello.seed);
+            run_window(Session::new(game, transport, local, options.delay, host));
+        }
+    }
+}
+
+fn run_window<G>(session: Session<G, UdpTransport>)
+where
+    G: RollbackGame + Arena + Send + Sync + 'static,
+    G::State: Send + Sync,
+{
+    let mut app = arena_app(NetPlay { session, accumulator: 0.0 });
+    app.add_system(advance::<G>.before(ArenaViewSet)).run();
+}
+
+// 本机玩家用 ← → 或 A/D，也可以用任意手柄；按固定步长推进会话
+fn advance<G>(
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut net: ResMut<NetPlay<G>>,
+    mut exit: EventWriter<bevy::app::AppExit>,
+) where
+    G: RollbackGame + Send + Sync + 'static,
+    G::State: Send + Sync,
+{
+    if keyboard.just_pressed(KeyCode::Escape) {
+        exit.send(bevy::app::AppExit);
+    }
+
+    let mut direction = 0.0;
+    if keyboard.any_pressed([KeyCode::Left, KeyCode::A]) { direction -= 1.0; }
+    if keyboard.any_pressed([KeyCode::Right, KeyCode::D]) { direction += 1.0; }
+    for gamepad in gamepads.iter() {
+        let stick = gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0);
+        if stick.abs() > 0.2 { direction += stick; }
+        if gamepad_buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadLeft)) { direction -= 1.0; }
+        if gamepad_buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadRight)) { direction += 1.0; }
+    }
+
+    net.accumulator += time.delta_seconds().min(MAX_FRAME_TIME);
+    while net.accumulator >= STEP {
+        net.accumulator -= STEP;
+        net.session.tick(direction);
+    }
+}
+
+// 无窗口的联机测试，双方都同步跑完指定帧数并且校验和一致时返回 true
+pub fn run_test(options: NetOptions, level: Level, config: GameConfig) -> bool {
+    let (transport, local, hello) = match options.connect() {
+        Ok(connection) => connection,
+        Err(err) => {
+            eprintln!(" 联机失败：{}", err);
+            return false;
+        }
+    };
+    let host = options.host.then_some(hello);
+    match hello.mode {
+        NetMode::Versus => {
+            let game = Versus::new(config, level, hello.seed);
+            play_test(Session::new(game, transport, local, options.delay, host), options.frames)
+        }
+        NetMode::Coop => {
+            let game = coop_game(config, level, hello.seed);
+            play_test(Session::new(game, transport, local, options.delay, host), options.frames)
+        }
+    }
+}
+
+// 按实际的 60 帧每秒运行，输入随机游走，经常变化才能触发回滚
+fn play_test<G: RollbackGame, T: Transport>(mut session: Session<G, T>, frames: u32) -> bool {
+    let mut rng = StdRng::seed_from_u64(session.local as u64);
+    let mut direction = 0.0;
+    let last_check = frames / CHECKSUM_INTERVAL * CHECKSUM_INTERVAL;
+    let mut next_tick = Instant::now();
+    let mut finished_at = None::<Instant>;
+
+    loop {
+        if session.frame() < frames {
+            if rng.gen_bool(0.1) {
+                direction = [-1.0, 0.0, 1.0, rng.gen_range(-1.0..1.0)][rng.gen_range(0..4)];
+            }
+            session.tick(direction);
+        } else {
+            session.poll();
+        }
+
+        let done = session.frame() >= frames && session.verified >= last_check;
+        if session.desync.is_some() || session.disconnected() {
+            break;
+        }
+        if done {
+            let finished = *finished_at.get_or_insert_with(Instant::now);
+            if finished.elapsed() >= LINGER {
+                break;
+            }
+        }
+        next_tick += Duration::from_secs_f32(STEP);
+        std::thread::sleep(next_tick.saturating_duration_since(Instant::now()));
+    }
+
+    let stats = &session.stats;
+    println!(
+        " 帧数 {}，回滚 {} 次（共重算 {} 帧，最长 {} 帧），等待 {} 次，比较校验和 {} 个，最后一致于第 {} 帧",
+        session.frame(), stats.rollbacks, stats.resimulated, stats.max_rollback, stats.stalls, stats.checksums, session.verified,
+    );
+    if let Some(frame) = session.desync {
+        eprintln!(" ❌ 第 {} 帧起不同步", frame);
+        false
+    } else if session.verified < last_check {
+        eprintln!(" ❌ 没能完成校验（对方断开或超时）");
+        false
+    } else {
+        println!(" ✅ 双方同步");
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use super::*;
+
+    // 内存中的一条单向链路：消息按随机延迟（以测试的帧计）送达，延迟不同就会乱序，另有一定概率丢失
+    type Queue = Rc<RefCell<Vec<(u32, Vec<u8>)>>>;
+
+    struct MemoryTransport {
+        outgoing: Queue,
+        incoming: Queue,
+        clock: Rc<Cell<u32>>,
+        rng: StdRng,
+    }
+
+    const LOSS: f64 = 0.2;
+    const MAX_DELAY: u32 = 4;
+
+    impl Transport for MemoryTransport {
+        fn send(&mut self, bytes: &[u8]) {
+            if self.rng.gen_bool(LOSS) {
+                return;
+            }
+            let due = self.clock.get() + self.rng.gen_range(0..=MAX_DELAY);
+            self.outgoing.borrow_mut().push((due, bytes.to_vec()));
+        }
+
+        fn recv(&mut self) -> Option<Vec<u8>> {
+            let mut incoming = self.incoming.borrow_mut();
+            let index = incoming.iter().position(|(due, _)| *due <= self.clock.get())?;
+            Some(incoming.remove(index).1)
+        }
+    }
+
+    fn memory_link(clock: &Rc<Cell<u32>>) -> (MemoryTransport, MemoryTransport) {
+        let (forward, backward) = (Queue::default(), Queue::default());
+        let transport = |outgoing: &Queue, incoming: &Queue, seed| MemoryTransport {
+            outgoing: outgoing.clone(),
+            incoming: incoming.clone(),
+            clock: clock.clone(),
+            rng: StdRng::seed_from_u64(seed),
+        };
+        (transport(&forward, &backward, 1), transport(&backward, &forward, 2))
+    }
+
+    // 两端在同一个线程里交替推进，输入随机游走，跑完后继续收发直到最后一个校验和比较完
+    fn play<G: RollbackGame>(host: G, guest: G, frames: u32) {
+        let clock = Rc::new(Cell::new(0));
+        let (host_link, guest_link) = memory_link(&clock);
+        let mut sessions = [
+            Session::new(host, host_link, 0, DEFAULT_DELAY, None),
+            Session::new(guest, guest_link, 1, DEFAULT_DELAY, None),
+        ];
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut directions = [0.0; PLAYERS];
+        let last_check = frames / CHECKSUM_INTERVAL * CHECKSUM_INTERVAL;
+
+        for _ in 0..frames * 4 {
+            clock.set(clock.get() + 1);
+            for (session, direction) in sessions.iter_mut().zip(&mut directions) {
+                if rng.gen_bool(0.1) {
+                    *direction = [-1.0, 0.0, 1.0, rng.gen_range(-1.0..1.0)][rng.gen_range(0..4)];
+                }
+                if session.frame() < frames {
+                    session.tick(*direction);
+                } else {
+                    session.poll();
+                }
+            }
+            if sessions.iter().all(|session| session.verified >= last_check) {
+                break;
+            }
+        }
+
+        for session in &sessions {
+            assert!(session.desync.is_none(), "第 {:?} 帧起不同步", session.desync);
+            assert!(session.verified >= last_check, "只校验到第 {} 帧", session.verified);
+            assert!(session.stats.rollbacks > 0);
+        }
+    }
+
+    #[test]
+    fn coop_stays_in_sync() {
+        let game = || coop_game(GameConfig::default(), Level::default(), 11);
+        play(game(), game(), 600);
+    }
+
+    #[test]
+    fn versus_stays_in_sync() {
+        let game = || Versus::new(GameConfig::default(), Level::default(), 13);
+        play(game(), game(), 600);
+    }
+}
@@ -0,0 +1,205 @@
+// 粒子系统：可配置的发射器 + 预先创建的粒子池
+use std::ops::Range;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::theme::lerp_color;
+
+const POOL_SIZE: usize = 256;      // 启动时预先创建的粒子数量
+const MAX_PARTICLES: usize = 1024; // 粒子池上限，超过后丢弃新粒子
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParticlePool>()
+            .add_event::<ParticleBurst>()
+            .add_startup_system(setup_particle_pool)
+            .add_system(emit_particle_bursts)
+            .add_system(update_particles.after(emit_particle_bursts));
+    }
+}
+
+// 发射器参数
+#[derive(Clone)]
+pub struct ParticleEmitter {
+    pub color_start: Color,
+    pub color_end: Color,       // 颜色随生命周期从起始色渐变到结束色
+    pub gravity: Vec2,
+    pub drag: f32,              // 每秒速度衰减比例
+    pub size_start: f32,
+    pub size_end: f32,
+    pub speed: Range<f32>,
+    pub lifetime: Range<f32>,
+    pub burst_count: usize,
+}
+
+impl ParticleEmitter {
+    // 砖块碎裂：使用砖块本身的颜色，碎片受重力下落
+    pub fn brick_burst(color: Color) -> Self {
+        Self {
+            color_start: color,
+            color_end: color.with_a(0.0),
+            gravity: Vec2::new(0.0, -400.0),
+            drag: 1.5,
+            size_start: 5.0,
+            size_end: 1.5,
+            speed: 80.0..260.0,
+            lifetime: 0.5..1.2,
+            burst_count: 12,
+        }
+    }
+}
+
+// 发射一次粒子爆发
+pub struct ParticleBurst {
+    pub position: Vec3,
+    pub emitter: ParticleEmitter,
+}
+
+#[derive(Component)]
+pub struct Particle {
+    velocity: Vec2,
+    lifetime: f32,
+    max_lifetime: f32,
+    color_start: Color,
+    color_end: Color,
+    gravity: Vec2,
+    drag: f32,
+    size_start: f32,
+    size_end: f32,
+}
+
+impl Particle {
+    fn inactive() -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            lifetime: 0.0,
+            max_lifetime: 1.0,
+            color_start: Color::NONE,
+            color_end: Color::NONE,
+            gravity: Vec2::ZERO,
+            drag: 0.0,
+            size_start: 0.0,
+            size_end: 0.0,
+        }
+    }
+}
+
+// 空闲粒子实体列表
+#[derive(Resource, Default)]
+pub struct ParticlePool {
+    free: Vec<Entity>,
+    total: usize,
+}
+
+fn spawn_pooled_particle(commands: &mut Commands) -> Entity {
+    commands.spawn((
+        SpriteBundle {
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Particle::inactive(),
+    )).id()
+}
+
+fn setup_particle_pool(mut commands: Commands, mut pool: ResMut<ParticlePool>) {
+    for _ in 0..POOL_SIZE {
+        let entity = spawn_pooled_particle(&mut commands);
+        pool.free.push(entity);
+    }
+    pool.total = POOL_SIZE;
+}
+
+// 从粒子池中取出实体并初始化，池不够用时才创建新实体
+fn emit_particle_bursts(
+    mut commands: Commands,
+    mut bursts: EventReader<ParticleBurst>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(&mut Particle, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for burst in bursts.iter() {
+        let emitter = &burst.emitter;
+        for _ in 0..emitter.burst_count {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.gen_range(emitter.speed.clone());
+            let lifetime = rng.gen_range(emitter.lifetime.clone());
+            let particle = Particle {
+                velocity: Vec2::from_angle(angle) * speed,
+                lifetime,
+                max_lifetime: lifetime,
+                color_start: emitter.color_start,
+                color_end: emitter.color_end,
+                gravity: emitter.gravity,
+                drag: emitter.drag,
+                size_start: emitter.size_start,
+                size_end: emitter.size_end,
+            };
+
+            match pool.free.pop() {
+                Some(entity) => {
+                    if let Ok((mut slot, mut transform, mut sprite, mut visibility)) = particle_query.get_mut(entity) {
+                        *slot = particle;
+                        transform.translation = burst.position;
+                        sprite.color = emitter.color_start;
+                        sprite.custom_size = Some(Vec2::splat(emitter.size_start));
+                        *visibility = Visibility::Inherited;
+                    }
+                }
+                // 新建的实体要到下一帧才能查询，这里直接带上初始状态
+                None if pool.total < MAX_PARTICLES => {
+                    let entity = spawn_pooled_particle(&mut commands);
+                    commands.entity(entity).insert((
+                        Transform::from_translation(burst.position),
+                        Sprite {
+                            color: emitter.color_start,
+                            custom_size: Some(Vec2::splat(emitter.size_start)),
+                            ..default()
+                        },
+                        Visibility::Inherited,
+                        particle,
+                    ));
+                    pool.total += 1;
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+// 更新粒子运动、颜色和大小，过期的粒子回收到池中
+fn update_particles(
+    time: Res<Time>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(Entity, &mut Transform, &mut Particle, &mut Sprite, &mut Visibility)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (entity, mut transform, mut particle, mut sprite, mut visibility) in particle_query.iter_mut() {
+        if particle.lifetime <= 0.0 {
+            continue;
+        }
+
+        // 重力和阻力
+        let gravity = particle.gravity;
+        let drag = (1.0 - particle.drag * delta).max(0.0);
+        particle.velocity = (particle.velocity + gravity * delta) * drag;
+        transform.translation += (particle.velocity * delta).extend(0.0);
+
+        particle.lifetime -= delta;
+        if particle.lifetime <= 0.0 {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+
+        // 生命进度 0 -> 1
+        let t = 1.0 - particle.lifetime / particle.max_lifetime;
+        sprite.color = lerp_color(particle.color_start, particle.color_end, t);
+        let size = particle.size_start + (particle.size_end - particle.size_start) * t;
+        sprite.custom_size = Some(Vec2::splat(size));
+    }
+}
@@ -0,0 +1,141 @@
+// 世界坐标下的分数弹框，靠得很近的弹框会合并显示
+use bevy::prelude::*;
+
+use crate::theme::Theme;
+
+const POPUP_LIFETIME: f32 = 1.2;
+const POPUP_RISE: f32 = 40.0;      // 整个生命周期内上升的距离
+const POPUP_Z: f32 = 50.0;         // 位于砖块和球之上、黑边之下
+const MERGE_RADIUS: f32 = 60.0;    // 合并距离
+const MERGE_WINDOW: f32 = 0.3;     // 弹框出现后多长时间内允许合并
+
+pub struct PopupPlugin;
+
+impl Plugin for PopupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScorePopupEvent>()
+            .add_system(spawn_score_popups)
+            .add_system(update_score_popups.after(spawn_score_popups));
+    }
+}
+
+// 请求在某个世界坐标显示得分
+pub struct ScorePopupEvent {
+    pub position: Vec3,
+    pub points: u32,
+    pub multiplier: u32,
+    pub color: Color,
+}
+
+#[derive(Component)]
+pub struct ScorePopup {
+    points: u32,
+    multiplier: u32,
+    origin: Vec2,
+    lifetime: f32,
+    color: Color,
+}
+
+impl ScorePopup {
+    fn label(&self) -> String {
+        if self.multiplier > 1 {
+            format!("+{} x{}", self.points, self.multiplier)
+        } else {
+            format!("+{}", self.points)
+        }
+    }
+
+    // 附近刚出现的同倍率弹框直接累加分数，合并成功时返回 true
+    // 合并不延长寿命，否则连续击碎时同一个弹框会一直存在、越加越大
+    fn merge(&mut self, event: &ScorePopupEvent) -> bool {
+        let position = event.position.truncate();
+        if self.multiplier != event.multiplier
+            || POPUP_LIFETIME - self.lifetime >= MERGE_WINDOW
+            || self.origin.distance(position) >= MERGE_RADIUS
+        {
+            return false;
+        }
+        self.points += event.points;
+        self.origin = (self.origin + position) / 2.0;
+        true
+    }
+}
+
+// 颜色整体提亮，保证在同色砖块上也能看清
+fn popup_color(color: Color) -> Color {
+    let [r, g, b, _] = color.as_rgba_f32();
+    Color::rgb(0.4 + r * 0.6, 0.4 + g * 0.6, 0.4 + b * 0.6)
+}
+
+fn spawn_score_popups(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut popup_events: EventReader<ScorePopupEvent>,
+    mut popup_query: Query<(&mut ScorePopup, &mut Text)>,
+) {
+    // 本帧新建的弹框要到命令执行后才能查询到，先放在这里，同一帧的多个事件也互相合并
+    let mut created: Vec<ScorePopup> = Vec::new();
+    'events: for event in popup_events.iter() {
+        for (mut popup, mut text) in popup_query.iter_mut() {
+            if popup.merge(event) {
+                text.sections[0].value = popup.label();
+                continue 'events;
+            }
+        }
+        if created.iter_mut().any(|popup| popup.merge(event)) {
+            continue;
+        }
+        created.push(ScorePopup {
+            points: event.points,
+            multiplier: event.multiplier,
+            origin: event.position.truncate(),
+            lifetime: POPUP_LIFETIME,
+            color: popup_color(event.color),
+        });
+    }
+
+    for popup in created {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    popup.label(),
+                    TextStyle {
+                        font: Default::default(),
+                        font_size: theme.fonts().popup,
+                        color: popup.color,
+                    },
+                ).with_alignment(TextAlignment::Center),
+                transform: Transform::from_translation(popup.origin.extend(POPUP_Z)),
+                ..default()
+            },
+            popup,
+        ));
+    }
+}
+
+// 弹框先放大弹出再缩回，同时减速上升并淡出
+fn update_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popup_query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in popup_query.iter_mut() {
+        popup.lifetime -= time.delta_seconds();
+        if popup.lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let t = 1.0 - popup.lifetime / POPUP_LIFETIME;
+        let rise = 1.0 - (1.0 - t) * (1.0 - t);
+        transform.translation = (popup.origin + Vec2::Y * rise * POPUP_RISE).extend(POPUP_Z);
+
+        // 前 20% 的时间从 1.5 倍缩回原大小
+        let pop = (1.0 - t / 0.2).max(0.0);
+        transform.scale = Vec3::splat(1.0 + 0.5 * pop * pop);
+
+        // 后半段淡出
+        let alpha = ((1.0 - t) * 2.0).min(1.0);
+        text.sections[0].style.color = popup.color.with_a(alpha);
+    }
+}
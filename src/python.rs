@@ -0,0 +1,227 @@
+// Python 绑定：打开 cargo feature "python" 后用 maturin 构建成 brick_breaker 模块
+// 观测是 NumPy 数组，step 期间释放 GIL，可以在多个 Python 线程中同时运行环境
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::gym::{Action, BrickBreakerEnv, EnvConfig, Observation, StepInfo, VecEnv, FEATURE_COUNT};
+use crate::level::Level;
+use crate::replay::Replay;
+
+fn errors_to_py(errors: Vec<String>) -> PyErr {
+    PyValueError::new_err(errors.join("\n"))
+}
+
+#[pyclass(name = "Level")]
+#[derive(Clone)]
+struct PyLevel {
+    inner: Level,
+}
+
+#[pymethods]
+impl PyLevel {
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.name.clone()
+    }
+
+    #[getter]
+    fn rows(&self) -> Vec<String> {
+        self.inner.rows.clone()
+    }
+
+    // 每块砖的 (行, 列, 分值, 耐久)
+    fn bricks(&self) -> Vec<(usize, usize, u32, u32)> {
+        self.inner.bricks().map(|(row, col, brick)| (row, col, brick.points, brick.hp)).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Level(name={:?}, bricks={})", self.inner.name, self.inner.bricks().count())
+    }
+}
+
+#[pyfunction]
+fn load_level(path: &str) -> PyResult<PyLevel> {
+    Level::load(path.as_ref()).map(|inner| PyLevel { inner }).map_err(errors_to_py)
+}
+
+#[pyfunction]
+fn parse_level(source: &str) -> PyResult<PyLevel> {
+    Level::parse(source).map(|inner| PyLevel { inner }).map_err(errors_to_py)
+}
+
+// 返回 {"seed", "frame_skip", "actions"}，用同样配置的 Env.reset(seed) 后依次 step 即可重现
+#[pyfunction]
+fn decode_replay(py: Python<'_>, data: &[u8]) -> PyResult<PyObject> {
+    let replay = Replay::decode(data).map_err(PyValueError::new_err)?;
+    let dict = PyDict::new(py);
+    dict.set_item("seed", replay.seed)?;
+    dict.set_item("frame_skip", replay.frame_skip)?;
+    dict.set_item("actions", replay.actions.iter().map(|action| action.index()).collect::<Vec<_>>())?;
+    Ok(dict.into())
+}
+
+fn env_config(
+    frame_size: Option<(usize, usize)>,
+    frame_skip: u32,
+    max_steps: u32,
+    level: Option<PyLevel>,
+) -> EnvConfig {
+    EnvConfig {
+        level: level.map(|level| level.inner).unwrap_or_default(),
+        frame_skip,
+        max_steps,
+        frame_size,
+        ..Default::default()
+    }
+}
+
+fn action_from_py(action: usize) -> PyResult<Action> {
+    if action < Action::COUNT {
+        Ok(Action::from_index(action))
+    } else {
+        Err(PyValueError::new_err(format!("动作编号必须小于 {}", Action::COUNT)))
+    }
+}
+
+// {"features": (FEATURE_COUNT,) float32，"frame": (高, 宽) uint8，只在设置了 frame_size 时出现}
+fn observation_to_py(py: Python<'_>, observation: Observation, config: &EnvConfig) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("features", observation.features.into_pyarray(py))?;
+    if let (Some(frame), Some((width, height))) = (observation.frame, config.frame_size) {
+        dict.set_item("frame", frame.into_pyarray(py).reshape([height, width])?)?;
+    }
+    Ok(dict.into())
+}
+
+fn info_to_py(py: Python<'_>, info: &StepInfo) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("score", info.score)?;
+    dict.set_item("lives", info.lives)?;
+    dict.set_item("bricks_left", info.bricks_left)?;
+    dict.set_item("won", info.won)?;
+    dict.set_item("truncated", info.truncated)?;
+    dict.set_item("steps", info.steps)?;
+    Ok(dict.into())
+}
+
+#[pyclass(name = "Env")]
+struct PyEnv {
+    inner: BrickBreakerEnv,
+}
+
+#[pymethods]
+impl PyEnv {
+    #[new]
+    #[pyo3(signature = (frame_size=None, frame_skip=4, max_steps=10_000, level=None))]
+    fn new(frame_size: Option<(usize, usize)>, frame_skip: u32, max_steps: u32, level: Option<PyLevel>) -> Self {
+        Self {
+            inner: BrickBreakerEnv::new(env_config(frame_size, frame_skip, max_steps, level)),
+        }
+    }
+
+    fn reset(&mut self, py: Python<'_>, seed: u64) -> PyResult<PyObject> {
+        let observation = py.allow_threads(|| self.inner.reset(seed));
+        observation_to_py(py, observation, self.inner.config())
+    }
+
+    // 返回 (observation, reward, done, info)
+    fn step(&mut self, py: Python<'_>, action: usize) -> PyResult<(PyObject, f32, bool, PyObject)> {
+        let action = action_from_py(action)?;
+        let (observation, reward, done, info) = py.allow_threads(|| self.inner.step(action));
+        Ok((observation_to_py(py, observation, self.inner.config())?, reward, done, info_to_py(py, &info)?))
+    }
+
+    // 当前这一局的回放，可以用 decode_replay 解码
+    fn replay<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        PyBytes::new(py, &self.inner.replay().encode())
+    }
+}
+
+// 多个环境批量推进，观测按第一维堆叠；结束的环境自动重开
+#[pyclass(name = "VecEnv")]
+struct PyVecEnv {
+    inner: VecEnv,
+    config: EnvConfig,
+}
+
+impl PyVecEnv {
+    fn stack(&self, py: Python<'_>, observations: Vec<Observation>) -> PyResult<PyObject> {
+        let count = observations.len();
+        let mut features = Vec::with_capacity(count * FEATURE_COUNT);
+        let mut frames = Vec::new();
+        for observation in observations {
+            features.extend(observation.features);
+            frames.extend(observation.frame.unwrap_or_default());
+        }
+
+        let dict = PyDict::new(py);
+        dict.set_item("features", features.into_pyarray(py).reshape([count, FEATURE_COUNT])?)?;
+        if let Some((width, height)) = self.config.frame_size {
+            dict.set_item("frame", frames.into_pyarray(py).reshape([count, height, width])?)?;
+        }
+        Ok(dict.into())
+    }
+}
+
+#[pymethods]
+impl PyVecEnv {
+    #[new]
+    #[pyo3(signature = (count, frame_size=None, frame_skip=4, max_steps=10_000, level=None))]
+    fn new(count: usize, frame_size: Option<(usize, usize)>, frame_skip: u32, max_steps: u32, level: Option<PyLevel>) -> Self {
+        let config = env_config(frame_size, frame_skip, max_steps, level);
+        Self {
+            inner: VecEnv::new(count, config.clone()),
+            config,
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn reset(&mut self, py: Python<'_>, seed: u64) -> PyResult<PyObject> {
+        let observations = py.allow_threads(|| self.inner.reset(seed));
+        self.stack(py, observations)
+    }
+
+    // 返回 (observations, rewards, dones, infos)
+    fn step(&mut self, py: Python<'_>, actions: Vec<usize>) -> PyResult<(PyObject, Py<PyArray1<f32>>, Py<PyArray1<bool>>, Vec<PyObject>)> {
+        let actions = actions.into_iter().map(action_from_py).collect::<PyResult<Vec<_>>>()?;
+        if actions.len() != self.inner.len() {
+            return Err(PyValueError::new_err(format!("需要 {} 个动作", self.inner.len())));
+        }
+
+        let results = py.allow_threads(|| self.inner.step(&actions));
+        let mut observations = Vec::with_capacity(results.len());
+        let mut rewards = Vec::with_capacity(results.len());
+        let mut dones = Vec::with_capacity(results.len());
+        let mut infos = Vec::with_capacity(results.len());
+        for (observation, reward, done, info) in results {
+            observations.push(observation);
+            rewards.push(reward);
+            dones.push(done);
+            infos.push(info_to_py(py, &info)?);
+        }
+        Ok((
+            self.stack(py, observations)?,
+            rewards.into_pyarray(py).into(),
+            dones.into_pyarray(py).into(),
+            infos,
+        ))
+    }
+}
+
+#[pymodule]
+fn brick_breaker(_py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add_class::<PyEnv>()?;
+    module.add_class::<PyVecEnv>()?;
+    module.add_class::<PyLevel>()?;
+    module.add_function(wrap_pyfunction!(load_level, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_level, module)?)?;
+    module.add_function(wrap_pyfunction!(decode_replay, module)?)?;
+    module.add("FEATURE_COUNT", FEATURE_COUNT)?;
+    module.add("ACTION_COUNT", Action::COUNT)?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// 回放：模拟是确定性的，只要记录种子、每步帧数和动作序列就能重现一局
+// 二进制格式（小端）：魔数 "BBRP"、版本 u8、种子 u64、每步帧数 u32、动作数 u32、每个动作一个字节
+use crate::gym::Action;
+
+const MAGIC: &[u8; 4] = b"BBRP";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 4 + 1 + 8 + 4 + 4;
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Replay {
+    pub seed: u64,
+    pub frame_skip: u32,
+    pub actions: Vec<Action>,
+}
+
+impl Replay {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.actions.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.frame_skip.to_le_bytes());
+        bytes.extend_from_slice(&(self.actions.len() as u32).to_le_bytes());
+        bytes.extend(self.actions.iter().map(|action| action.index() as u8));
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err("不是回放文件".to_string());
+        }
+        if bytes[4] != VERSION {
+            return Err(format!("不支持的回放版本 {}", bytes[4]));
+        }
+
+        let seed = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        let frame_skip = u32::from_le_bytes(bytes[13..17].try_into().unwrap());
+        let count = u32::from_le_bytes(bytes[17..21].try_into().unwrap()) as usize;
+        let body = &bytes[HEADER_LEN..];
+        if body.len() != count {
+            return Err(format!("回放应有 {} 个动作，实际 {} 个", count, body.len()));
+        }
+        let actions = body
+            .iter()
+            .map(|&index| match index as usize {
+                index if index < Action::COUNT => Ok(Action::from_index(index)),
+                index => Err(format!("无效的动作编号 {}", index)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { seed, frame_skip, actions })
+    }
+}
@@ -0,0 +1,159 @@
+// 计分规则：连击倍率、整行奖励、速通奖励、剩余生命奖励和无尽模式的存活奖励
+// 这里只放纯逻辑，不依赖具体的实体和系统
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+const MAX_MULTIPLIER: u32 = 5;        // 连击倍率上限
+const ROW_CLEAR_BONUS: u32 = 100;     // 清空一整行的奖励
+const QUICK_CLEAR_TIME: f32 = 90.0;   // 在该时间（秒）内通关才有速通奖励
+const QUICK_CLEAR_BONUS_PER_SECOND: u32 = 10;
+const LIFE_BONUS: u32 = 200;          // 每条剩余生命的奖励
+const SURVIVAL_BONUS_PER_SECOND: u32 = 5;  // 无尽模式中每存活一秒的奖励
+
+// 本关得分明细，在通关界面显示
+#[derive(Default, Clone, Copy)]
+pub struct ScoreBreakdown {
+    pub bricks: u32,       // 砖块基础分
+    pub combo_bonus: u32,  // 连击倍率带来的额外分
+    pub row_bonus: u32,
+    pub time_bonus: u32,
+    pub lives_bonus: u32,
+    pub survival_bonus: u32,  // 无尽模式
+}
+
+#[derive(Resource, Default, Clone)]
+pub struct Scoring {
+    combo: u32,                   // 上次碰到挡板后连续击碎的砖块数
+    pub level_time: f32,          // 本关已用时间（秒）
+    rows_alive: HashMap<u32, f32>, // 仍有砖块的行及其 y 坐标
+    pub breakdown: ScoreBreakdown,
+    pub bricks_broken: u32,
+    survival_seconds: u32,        // 已发放存活奖励的秒数
+}
+
+impl Scoring {
+    pub fn combo(&self) -> u32 {
+        self.combo
+    }
+
+    // 当前连击对应的倍率：第1块 x1，第2块 x2……
+    pub fn multiplier(&self) -> u32 {
+        (self.combo + 1).min(MAX_MULTIPLIER)
+    }
+
+    // 击碎一块砖块，返回实际得分和使用的倍率
+    pub fn register_brick(&mut self, points: u32) -> (u32, u32) {
+        let multiplier = self.multiplier();
+        let awarded = points * multiplier;
+        self.combo += 1;
+        self.bricks_broken += 1;
+        self.breakdown.bricks += points;
+        self.breakdown.combo_bonus += awarded - points;
+        (awarded, multiplier)
+    }
+
+    // 球碰到挡板或掉落时连击中断
+    pub fn reset_combo(&mut self) {
+        self.combo = 0;
+    }
+
+    // 用当前仍有砖块的行更新记录，返回刚被清空的行的 y 坐标和奖励
+    pub fn update_rows(&mut self, rows: &HashMap<u32, f32>) -> Vec<(f32, u32)> {
+        let mut cleared = Vec::new();
+        for (row, y) in self.rows_alive.iter() {
+            if !rows.contains_key(row) {
+                cleared.push((*y, ROW_CLEAR_BONUS));
+            }
+        }
+        self.breakdown.row_bonus += cleared.len() as u32 * ROW_CLEAR_BONUS;
+        self.rows_alive.clone_from(rows);
+        cleared
+    }
+
+    // 无尽模式中砖块整体下移一行，已记录的行号和位置跟着移动，不算作清空
+    pub fn shift_rows(&mut self, offset: f32) {
+        self.rows_alive = self.rows_alive.drain().map(|(row, y)| (row + 1, y + offset)).collect();
+    }
+
+    // 无尽模式：本关用时每满一秒发放一次存活奖励，返回新增的奖励
+    pub fn update_survival(&mut self) -> u32 {
+        let seconds = self.level_time as u32;
+        let bonus = seconds.saturating_sub(self.survival_seconds) * SURVIVAL_BONUS_PER_SECOND;
+        self.survival_seconds = self.survival_seconds.max(seconds);
+        self.breakdown.survival_bonus += bonus;
+        bonus
+    }
+
+    // 通关结算，返回速通奖励和生命奖励之和
+    pub fn finish_level(&mut self, lives: u32) -> u32 {
+        let seconds_left = (QUICK_CLEAR_TIME - self.level_time).max(0.0);
+        self.breakdown.time_bonus = seconds_left as u32 * QUICK_CLEAR_BONUS_PER_SECOND;
+        self.breakdown.lives_bonus = lives * LIFE_BONUS;
+        self.breakdown.time_bonus + self.breakdown.lives_bonus
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combo_grows_until_cap() {
+        let mut scoring = Scoring::default();
+        let awarded: Vec<_> = (0..7).map(|_| scoring.register_brick(10)).collect();
+        assert_eq!(awarded, [(10, 1), (20, 2), (30, 3), (40, 4), (50, 5), (50, 5), (50, 5)]);
+        assert_eq!(scoring.combo(), 7);
+        assert_eq!(scoring.bricks_broken, 7);
+        assert_eq!(scoring.breakdown.bricks, 70);
+        assert_eq!(scoring.breakdown.combo_bonus, 250 - 70);
+    }
+
+    #[test]
+    fn reset_combo_restarts_multiplier() {
+        let mut scoring = Scoring::default();
+        scoring.register_brick(10);
+        scoring.register_brick(10);
+        assert_eq!(scoring.multiplier(), 3);
+
+        scoring.reset_combo();
+        assert_eq!(scoring.combo(), 0);
+        assert_eq!(scoring.register_brick(10), (10, 1));
+        // 连击中断不影响已经记下的分数
+        assert_eq!(scoring.breakdown.combo_bonus, 10);
+    }
+
+    #[test]
+    fn cleared_rows_pay_once() {
+        let mut scoring = Scoring::default();
+        let rows = HashMap::from([(0, 250.0), (1, 225.0), (2, 200.0)]);
+        assert!(scoring.update_rows(&rows).is_empty());
+
+        let rows = HashMap::from([(0, 250.0), (2, 200.0)]);
+        assert_eq!(scoring.update_rows(&rows), [(225.0, ROW_CLEAR_BONUS)]);
+        // 同一行不会再次发放奖励
+        assert!(scoring.update_rows(&rows).is_empty());
+
+        let mut cleared = scoring.update_rows(&HashMap::new());
+        cleared.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(cleared, [(200.0, ROW_CLEAR_BONUS), (250.0, ROW_CLEAR_BONUS)]);
+        assert_eq!(scoring.breakdown.row_bonus, 3 * ROW_CLEAR_BONUS);
+    }
+
+    #[test]
+    fn finish_level_bonuses() {
+        let mut scoring = Scoring { level_time: 60.5, ..default() };
+        assert_eq!(scoring.finish_level(2), 29 * QUICK_CLEAR_BONUS_PER_SECOND + 2 * LIFE_BONUS);
+        assert_eq!(scoring.breakdown.time_bonus, 290);
+        assert_eq!(scoring.breakdown.lives_bonus, 400);
+
+        // 超过速通时间没有速通奖励，生命奖励照常
+        let mut scoring = Scoring { level_time: QUICK_CLEAR_TIME + 5.0, ..default() };
+        assert_eq!(scoring.finish_level(1), LIFE_BONUS);
+        assert_eq!(scoring.breakdown.time_bonus, 0);
+    }
+}
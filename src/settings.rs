@@ -0,0 +1,302 @@
+// 设置面板：Tab 打开/关闭，打开时游戏暂停
+use bevy::prelude::*;
+
+use crate::editor::LevelEditor;
+use crate::audio::AudioSettings;
+use crate::autopilot::Autopilot;
+use crate::coop::Coop;
+use crate::fonts::{FontStatus, UiFont};
+use crate::juice::JuiceSettings;
+use crate::locale::Locale;
+use crate::theme::Theme;
+use crate::EffectSettings;
+
+const INTENSITY_STEP: f32 = 0.25;
+const MAX_INTENSITY: f32 = 2.0;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsMenu>()
+            .add_startup_system(spawn_settings_panel)
+            .add_system(navigate_settings_menu)
+            .add_system(render_settings_menu.after(navigate_settings_menu));
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SettingItem {
+    Language,
+    ReducedEffects,
+    Shake,
+    HitStop,
+    Flash,
+    Squash,
+    MasterVolume,
+    SfxVolume,
+    MusicVolume,
+    Theme,
+    ColorMode,
+    BrickGlyphs,
+    Outlines,
+    Autopilot,
+    CoopMode,
+    CoopLives,
+}
+
+const ITEMS: [SettingItem; 16] = [
+    SettingItem::Language,
+    SettingItem::ReducedEffects,
+    SettingItem::Shake,
+    SettingItem::HitStop,
+    SettingItem::Flash,
+    SettingItem::Squash,
+    SettingItem::MasterVolume,
+    SettingItem::SfxVolume,
+    SettingItem::MusicVolume,
+    SettingItem::Theme,
+    SettingItem::ColorMode,
+    SettingItem::BrickGlyphs,
+    SettingItem::Outlines,
+    SettingItem::Autopilot,
+    SettingItem::CoopMode,
+    SettingItem::CoopLives,
+];
+
+impl SettingItem {
+    fn label_key(self) -> &'static str {
+        match self {
+            SettingItem::Language => "settings.language",
+            SettingItem::ReducedEffects => "settings.reduced_effects",
+            SettingItem::Shake => "settings.shake",
+            SettingItem::HitStop => "settings.hit_stop",
+            SettingItem::Flash => "settings.flash",
+            SettingItem::Squash => "settings.squash",
+            SettingItem::MasterVolume => "settings.master_volume",
+            SettingItem::SfxVolume => "settings.sfx_volume",
+            SettingItem::MusicVolume => "settings.music_volume",
+            SettingItem::Theme => "settings.theme",
+            SettingItem::ColorMode => "settings.color_mode",
+            SettingItem::BrickGlyphs => "settings.brick_glyphs",
+            SettingItem::Outlines => "settings.outlines",
+            SettingItem::Autopilot => "settings.autopilot",
+            SettingItem::CoopMode => "settings.coop_mode",
+            SettingItem::CoopLives => "settings.coop_lives",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SettingsMenu {
+    pub open: bool,
+    selected: usize,
+}
+
+// 游戏逻辑系统的运行条件：设置面板或关卡编辑器打开时暂停
+pub fn gameplay_active(menu: Res<SettingsMenu>, editor: Res<LevelEditor>) -> bool {
+    !menu.open && !editor.active
+}
+
+#[derive(Component)]
+struct SettingsPanel;
+
+#[derive(Component)]
+struct SettingsText;
+
+#[derive(Component)]
+struct SettingsBackground;
+
+fn spawn_settings_panel(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        SettingsPanel,
+    )).with_children(|parent| {
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(24.0)),
+                    ..default()
+                },
+                background_color: theme.colors().panel.into(),
+                ..default()
+            },
+            SettingsBackground,
+        )).with_children(|panel| {
+            panel.spawn((TextBundle::default(), SettingsText));
+        });
+    });
+}
+
+// 强度滑块在 0 到 MAX_INTENSITY 之间按步长调整
+fn step_intensity(value: &mut f32, direction: f32) {
+    *value = (*value + direction * INTENSITY_STEP).clamp(0.0, MAX_INTENSITY);
+}
+
+fn navigate_settings_menu(
+    keyboard: Res<Input<KeyCode>>,
+    mut menu: ResMut<SettingsMenu>,
+    mut locale: ResMut<Locale>,
+    mut effect_settings: ResMut<EffectSettings>,
+    mut juice_settings: ResMut<JuiceSettings>,
+    mut theme: ResMut<Theme>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut autopilot: ResMut<Autopilot>,
+    mut coop: ResMut<Coop>,
+    ui_font: Option<Res<UiFont>>,
+) {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        menu.open = !menu.open;
+    }
+    if !menu.open {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::Up) {
+        menu.selected = (menu.selected + ITEMS.len() - 1) % ITEMS.len();
+    }
+    if keyboard.just_pressed(KeyCode::Down) {
+        menu.selected = (menu.selected + 1) % ITEMS.len();
+    }
+
+    let mut direction = 0.0;
+    if keyboard.just_pressed(KeyCode::Left) { direction -= 1.0; }
+    if keyboard.just_pressed(KeyCode::Right) { direction += 1.0; }
+    if direction == 0.0 {
+        return;
+    }
+
+    match ITEMS[menu.selected] {
+        SettingItem::Language => {
+            // 界面字体加载失败时默认字体显示不了中文，跳过这些语言
+            let font_failed = ui_font.is_some_and(|font| font.status == FontStatus::Failed);
+            let mut language = locale.language.next();
+            while font_failed && language.needs_cjk_font() {
+                language = language.next();
+            }
+            locale.language = language;
+        }
+        SettingItem::ReducedEffects => effect_settings.reduced_effects = !effect_settings.reduced_effects,
+        SettingItem::Shake => step_intensity(&mut juice_settings.shake_intensity, direction),
+        SettingItem::HitStop => step_intensity(&mut juice_settings.hit_stop_intensity, direction),
+        SettingItem::Flash => step_intensity(&mut juice_settings.flash_intensity, direction),
+        SettingItem::Squash => step_intensity(&mut juice_settings.squash_intensity, direction),
+        SettingItem::MasterVolume => AudioSettings::step(&mut audio_settings.master, direction),
+        SettingItem::SfxVolume => AudioSettings::step(&mut audio_settings.sfx, direction),
+        SettingItem::MusicVolume => AudioSettings::step(&mut audio_settings.music, direction),
+        SettingItem::Theme => theme.next_preset(),
+        SettingItem::ColorMode => {
+            let mode = theme.color_mode.next();
+            theme.set_color_mode(mode);
+        }
+        SettingItem::BrickGlyphs => theme.brick_glyphs = !theme.brick_glyphs,
+        SettingItem::Outlines => theme.outlines = !theme.outlines,
+        SettingItem::Autopilot => autopilot.difficulty = autopilot.difficulty.next(),
+        // 改变后重新生成挡板并开始新的一局
+        SettingItem::CoopMode => coop.next_mode(),
+        SettingItem::CoopLives => coop.shared_lives = !coop.shared_lives,
+    }
+}
+
+fn render_settings_menu(
+    menu: Res<SettingsMenu>,
+    locale: Res<Locale>,
+    effect_settings: Res<EffectSettings>,
+    juice_settings: Res<JuiceSettings>,
+    theme: Res<Theme>,
+    audio_settings: Res<AudioSettings>,
+    autopilot: Res<Autopilot>,
+    coop: Res<Coop>,
+    mut panel_query: Query<&mut Visibility, With<SettingsPanel>>,
+    mut background_query: Query<&mut BackgroundColor, With<SettingsBackground>>,
+    mut text_query: Query<&mut Text, With<SettingsText>>,
+) {
+    if !(menu.is_changed()
+        || locale.is_changed()
+        || effect_settings.is_changed()
+        || juice_settings.is_changed()
+        || theme.is_changed()
+        || audio_settings.is_changed()
+        || autopilot.is_changed()
+        || coop.is_changed())
+    {
+        return;
+    }
+
+    let colors = theme.colors();
+    let fonts = theme.fonts();
+    for mut background in background_query.iter_mut() {
+        *background = colors.panel.into();
+    }
+
+    for mut visibility in panel_query.iter_mut() {
+        *visibility = if menu.open { Visibility::Inherited } else { Visibility::Hidden };
+    }
+
+    let style = |color: Color, font_size: f32| TextStyle {
+        font: Default::default(),
+        font_size,
+        color,
+    };
+    let on_off = |on: bool| locale.get(if on { "settings.on" } else { "settings.off" }).to_string();
+    let percent = |value: f32| format!("{}%", (value * 100.0).round());
+
+    let mut sections = vec![TextSection::new(
+        format!("{}\n\n", locale.get("settings.title")),
+        style(colors.text_primary, fonts.settings_title),
+    )];
+    for (index, item) in ITEMS.iter().enumerate() {
+        let value = match item {
+            SettingItem::Language => locale.language.native_name().to_string(),
+            SettingItem::ReducedEffects => on_off(effect_settings.reduced_effects),
+            SettingItem::Shake => percent(juice_settings.shake_intensity),
+            SettingItem::HitStop => percent(juice_settings.hit_stop_intensity),
+            SettingItem::Flash => percent(juice_settings.flash_intensity),
+            SettingItem::Squash => percent(juice_settings.squash_intensity),
+            SettingItem::MasterVolume => percent(audio_settings.master),
+            SettingItem::SfxVolume => percent(audio_settings.sfx),
+            SettingItem::MusicVolume => percent(audio_settings.music),
+            SettingItem::Theme => {
+                let def = theme.def();
+                locale.get_or(&format!("theme.{}", def.id), &def.name).to_string()
+            }
+            SettingItem::ColorMode => locale.get(theme.color_mode.label_key()).to_string(),
+            SettingItem::BrickGlyphs => on_off(theme.brick_glyphs),
+            SettingItem::Outlines => on_off(theme.outlines),
+            SettingItem::Autopilot => locale.get(autopilot.difficulty.label_key()).to_string(),
+            SettingItem::CoopMode => locale.get(coop.mode_key()).to_string(),
+            SettingItem::CoopLives => {
+                locale.get(if coop.shared_lives { "coop.shared" } else { "coop.separate" }).to_string()
+            }
+        };
+        let selected = index == menu.selected;
+        let (marker, color) = if selected {
+            ("▶ ", colors.text_good)
+        } else {
+            ("  ", colors.text_normal)
+        };
+        sections.push(TextSection::new(
+            format!("{}{}: < {} >\n", marker, locale.get(item.label_key()), value),
+            style(color, fonts.settings_item),
+        ));
+    }
+    sections.push(TextSection::new(
+        format!("\n{}", locale.get("settings.hint")),
+        style(colors.text_info, fonts.help),
+    ));
+
+    for mut text in text_query.iter_mut() {
+        text.sections = sections.clone();
+    }
+}
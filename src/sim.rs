@@ -0,0 +1,304 @@
+// 无窗口的确定性模拟：固定步长、单线程、系统按固定顺序运行，同样的种子和操作得到同样的一局
+// 训练环境和压力测试都用它，不需要窗口、渲染和音频
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use bevy::ecs::event::Events;
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::audio::SfxEvent;
+use crate::coop::Coop;
+use crate::juice::HitStop;
+use crate::level::Level;
+use crate::particles::ParticleBurst;
+use crate::popups::ScorePopupEvent;
+use crate::scoring::Scoring;
+use crate::theme::Theme;
+use crate::{
+    apply_player_actions, check_collisions, check_win_condition, handle_restart, move_ball,
+    paddle_movement, spawn_brick_wall, spawn_new_ball, update_level_progress, Ball, Brick,
+    EffectSettings, GameConfig, GameRng, GameState, ImpactEvent, Paddle, PlayerInput, PlayerInputSet,
+};
+
+pub const STEP: f32 = 1.0 / 60.0;
+
+// 一帧的游戏逻辑，额外加入的系统用它排顺序
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SimulationSet;
+
+// 一帧的完整快照，联机回滚时用它恢复到之前的某一帧
+// 实体按固定顺序保存（挡板按玩家、球按编号、砖块按行和 x 坐标），同样的局面得到同样的快照
+#[derive(Clone)]
+pub struct SimState {
+    now: Instant,
+    time: Time,
+    game_state: GameState,
+    scoring: Scoring,
+    rng: GameRng,
+    config: GameConfig,
+    paddles: Vec<(Transform, Paddle)>,
+    balls: Vec<(Transform, Ball)>,
+    bricks: Vec<(Transform, Brick, Color)>,
+}
+
+impl SimState {
+    // 局面的校验和，联机双方定期交换比较以发现不同步
+    // 浮点数按位参与计算，时间和 HashMap 等与局面无关的内容不计入
+    pub fn checksum(&self) -> u64 {
+        let mut hasher = Fnv1a::default();
+        let state = &self.game_state;
+        (state.score, state.lives, state.game_over, state.won, state.next_ball_id).hash(&mut hasher);
+        (state.player_scores, state.player_lives).hash(&mut hasher);
+        (self.scoring.combo(), self.scoring.level_time.to_bits()).hash(&mut hasher);
+        self.rng.0.clone().gen::<u64>().hash(&mut hasher);
+        self.config.ball_speed.to_bits().hash(&mut hasher);
+        for (transform, paddle) in &self.paddles {
+            (paddle.player, paddle.width.to_bits(), transform.translation.x.to_bits()).hash(&mut hasher);
+        }
+        for (transform, ball) in &self.balls {
+            let position = transform.translation.truncate().to_array().map(f32::to_bits);
+            (ball.id, ball.last_player, position, ball.velocity.to_array().map(f32::to_bits)).hash(&mut hasher);
+        }
+        for (transform, brick, _) in &self.bricks {
+            let position = transform.translation.truncate().to_array().map(f32::to_bits);
+            (brick.row, brick.hp, brick.points, position).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+// FNV-1a：标准库的默认哈希算法没有规定，不同版本的程序可能算出不同的结果
+// 整数一律按小端、usize 按 u64 写入，不同平台的两端也能比较
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+pub struct Simulation {
+    pub world: World,
+    schedule: Schedule,
+    now: Instant,
+}
+
+fn spawn_world(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    level: Res<Level>,
+    coop: Res<Coop>,
+    mut rng: ResMut<GameRng>,
+) {
+    coop.reset_lives(&mut game_state, config.initial_lives);
+    for player in 0..coop.players {
+        let home = coop.paddle_home(player);
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(home.x, home.y, 0.0)),
+            Paddle { width: config.paddle_width, player },
+        ));
+    }
+    spawn_new_ball(&mut commands, &mut game_state, &theme, &config, &mut rng);
+    spawn_brick_wall(&mut commands, &theme, &config, &level);
+}
+
+// 主题只影响颜色，所有模拟共用第一次读入的主题，避免每次 reset 都重新读取和解析主题文件
+fn shared_theme() -> Theme {
+    static THEME: OnceLock<Theme> = OnceLock::new();
+    THEME.get_or_init(Theme::default).clone()
+}
+
+impl Simulation {
+    pub fn new(config: GameConfig, level: Level, seed: u64) -> Self {
+        Self::with_coop(config, level, seed, Coop::default())
+    }
+
+    // 指定合作模式，双人时两块挡板分别读取 PlayerInput 中各自的方向
+    pub fn with_coop(config: GameConfig, level: Level, seed: u64, coop: Coop) -> Self {
+        let mut world = World::new();
+        let now = Instant::now();
+        let mut time = Time::new(now);
+        time.update_with_instant(now);
+        world.insert_resource(time);
+        world.insert_resource(config);
+        world.insert_resource(level);
+        world.insert_resource(GameRng(StdRng::seed_from_u64(seed)));
+        world.init_resource::<GameState>();
+        world.init_resource::<EffectSettings>();
+        world.init_resource::<Scoring>();
+        world.insert_resource(shared_theme());
+        world.init_resource::<HitStop>();
+        world.init_resource::<PlayerInput>();
+        world.insert_resource(coop);
+        world.init_resource::<Events<ImpactEvent>>();
+        world.init_resource::<Events<ParticleBurst>>();
+        world.init_resource::<Events<ScorePopupEvent>>();
+        world.init_resource::<Events<SfxEvent>>();
+
+        let mut startup = Schedule::new();
+        startup.set_executor_kind(ExecutorKind::SingleThreaded).add_system(spawn_world);
+        startup.run(&mut world);
+
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor_kind(ExecutorKind::SingleThreaded)
+            .add_systems(
+                (
+                    Events::<ImpactEvent>::update_system,
+                    Events::<ParticleBurst>::update_system,
+                    Events::<ScorePopupEvent>::update_system,
+                    Events::<SfxEvent>::update_system,
+                )
+                    .before(PlayerInputSet),
+            )
+            .add_systems(
+                (
+                    paddle_movement,
+                    apply_player_actions,
+                    move_ball,
+                    check_collisions,
+                    update_level_progress,
+                    check_win_condition,
+                    handle_restart,
+                )
+                    .chain()
+                    .in_set(SimulationSet)
+                    .after(PlayerInputSet),
+            );
+
+        Self { world, schedule, now }
+    }
+
+    // 加入额外的系统，例如自动驾驶（放进 PlayerInputSet）或检查（放在 SimulationSet 之后）
+    pub fn add_system<M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self {
+        self.schedule.add_system(system);
+        self
+    }
+
+    pub fn save(&mut self) -> SimState {
+        let world = &mut self.world;
+        let mut paddles: Vec<_> = world
+            .query::<(&Transform, &Paddle)>()
+            .iter(world)
+            .map(|(transform, paddle)| (*transform, paddle.clone()))
+            .collect();
+        paddles.sort_unstable_by_key(|(_, paddle)| paddle.player);
+        let mut balls: Vec<_> = world
+            .query::<(&Transform, &Ball)>()
+            .iter(world)
+            .map(|(transform, ball)| (*transform, ball.clone()))
+            .collect();
+        balls.sort_unstable_by_key(|(_, ball)| ball.id);
+        let mut bricks: Vec<_> = world
+            .query::<(&Transform, &Brick, &Sprite)>()
+            .iter(world)
+            .map(|(transform, brick, sprite)| (*transform, brick.clone(), sprite.color))
+            .collect();
+        bricks.sort_unstable_by(|a, b| a.1.row.cmp(&b.1.row).then(a.0.translation.x.total_cmp(&b.0.translation.x)));
+
+        SimState {
+            now: self.now,
+            time: world.resource::<Time>().clone(),
+            game_state: world.resource::<GameState>().clone(),
+            scoring: world.resource::<Scoring>().clone(),
+            rng: world.resource::<GameRng>().clone(),
+            config: world.resource::<GameConfig>().clone(),
+            paddles,
+            balls,
+            bricks,
+        }
+    }
+
+    // 恢复快照：清空全部实体后按快照重新生成，砖块的装饰子实体不再生成（模拟用不到）
+    pub fn load(&mut self, state: &SimState) {
+        self.now = state.now;
+        let world = &mut self.world;
+        world.insert_resource(state.time.clone());
+        world.insert_resource(state.game_state.clone());
+        world.insert_resource(state.scoring.clone());
+        world.insert_resource(state.rng.clone());
+        world.insert_resource(state.config.clone());
+        world.clear_entities();
+        for (transform, paddle) in &state.paddles {
+            world.spawn((SpatialBundle::from_transform(*transform), paddle.clone()));
+        }
+        for (transform, ball) in &state.balls {
+            world.spawn((SpatialBundle::from_transform(*transform), ball.clone()));
+        }
+        for (transform, brick, color) in &state.bricks {
+            world.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: *color,
+                        custom_size: Some(brick.size),
+                        ..default()
+                    },
+                    transform: *transform,
+                    ..default()
+                },
+                brick.clone(),
+            ));
+        }
+    }
+
+    pub fn checksum(&mut self) -> u64 {
+        self.save().checksum()
+    }
+
+    // 推进一帧，时间固定增加 STEP，与实际运行速度无关
+    pub fn step(&mut self) {
+        self.now += Duration::from_secs_f32(STEP);
+        self.world.resource_mut::<Time>().update_with_instant(self.now);
+        self.schedule.run(&mut self.world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 标准 FNV-1a 64 位的测试向量，保证校验和的算法不会悄悄改变
+    #[test]
+    fn fnv1a_matches_reference() {
+        let mut hasher = Fnv1a::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+        let mut hasher = Fnv1a::default();
+        hasher.write(b"foobar");
+        assert_eq!(hasher.finish(), 0x8594_4171_f739_67e8);
+    }
+}
@@ -0,0 +1,189 @@
+// 压力测试：不开窗口，用固定步长快速模拟，让自动驾驶连续打很多局
+// 检查碰撞和通关判定长时间运行后是否还正确，以及关卡能否被打通；无尽模式下检查下移和结束判定
+use bevy::prelude::*;
+
+use crate::autopilot::{drive_paddle, Autopilot, Difficulty};
+use crate::config::{ConfigSource, GameConfig};
+use crate::coop::Coop;
+use crate::endless::{self, Endless};
+use crate::generator::GeneratorConfig;
+use crate::level::Level;
+use crate::sim::{Simulation, SimulationSet, STEP};
+use crate::{Ball, Brick, GameState, ImpactEvent, ImpactKind, PlayerInputSet, ARENA_HEIGHT, ARENA_WIDTH};
+
+const STUCK_TIME: f32 = 120.0;      // 这么多秒没有击碎砖块就认为球卡住了
+const REPORT_INTERVAL: f32 = 600.0; // 每模拟十分钟打印一次进度
+const OUT_OF_BOUNDS: f32 = 20.0;    // 球允许越过边界的距离（一帧的移动量加余量）
+
+#[derive(Resource, Default)]
+struct SoakStats {
+    games: u32,
+    wins: u32,
+    losses: u32,
+    bricks_broken: u64,
+    since_progress: f32,      // 距离上次击碎砖块的模拟时间
+    failure: Option<String>,
+}
+
+// 统计胜负，检查不变量，发现问题时记录原因
+fn monitor(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    config: Res<GameConfig>,
+    coop: Res<Coop>,
+    endless: Option<Res<Endless>>,
+    mut finished: Local<bool>,
+    mut impacts: EventReader<ImpactEvent>,
+    ball_query: Query<&Transform, With<Ball>>,
+    brick_query: Query<&Brick>,
+    mut stats: ResMut<SoakStats>,
+) {
+    let broken = impacts.iter().filter(|impact| impact.kind == ImpactKind::BrickBreak).count();
+    stats.bricks_broken += broken as u64;
+
+    if game_state.game_over {
+        if !*finished {
+            *finished = true;
+            stats.games += 1;
+            if game_state.won {
+                stats.wins += 1;
+            } else {
+                stats.losses += 1;
+            }
+        }
+        if game_state.won && endless.is_some() {
+            stats.failure = Some("无尽模式中判定了通关".to_string());
+        }
+        // 胜利时场上必须已经没有砖块
+        if game_state.won && brick_query.iter().any(|brick| brick.hp > 0 && !brick.indestructible) {
+            stats.failure = Some("判定胜利时仍有砖块".to_string());
+        }
+        stats.since_progress = 0.0;
+        return;
+    }
+    *finished = false;
+
+    stats.since_progress = if broken > 0 { 0.0 } else { stats.since_progress + time.delta_seconds() };
+    if stats.since_progress > STUCK_TIME {
+        let left = brick_query.iter().filter(|brick| !brick.indestructible).count();
+        stats.failure = Some(format!("连续 {} 秒没有击碎砖块，剩余 {} 块", STUCK_TIME, left));
+    }
+    // 分开计算生命时总生命是各玩家之和
+    let max_lives = if coop.shared_lives { config.initial_lives } else { config.initial_lives * coop.players as u32 };
+    if game_state.lives > max_lives {
+        stats.failure = Some(format!("生命数 {} 超过初始值", game_state.lives));
+    }
+    if !coop.shared_lives && game_state.lives != game_state.player_lives.iter().sum::<u32>() {
+        stats.failure = Some(format!("总生命 {} 与各玩家生命 {:?} 不符", game_state.lives, game_state.player_lives));
+    }
+    if ball_query.iter().count() > config.max_balls {
+        stats.failure = Some(format!("球数超过上限 {}", config.max_balls));
+    }
+    for transform in ball_query.iter() {
+        let position = transform.translation;
+        if position.x.abs() > ARENA_WIDTH / 2.0 + OUT_OF_BOUNDS || position.y > ARENA_HEIGHT / 2.0 + OUT_OF_BOUNDS {
+            stats.failure = Some(format!("球飞出了游戏区域: ({:.1}, {:.1})", position.x, position.y));
+        }
+    }
+    if brick_query.iter().any(|brick| brick.hp > brick.max_hp) {
+        stats.failure = Some("砖块耐久超过上限".to_string());
+    }
+}
+
+// 由完美水平的自动驾驶操作、每步检查不变量的模拟
+fn soak_simulation(level: Level, coop: Coop, endless: Option<GeneratorConfig>, seed: u64) -> Simulation {
+    let mut sim = Simulation::with_coop(ConfigSource::default().load(), level, seed, coop);
+    sim.world.init_resource::<SoakStats>();
+    sim.world.insert_resource(Autopilot::bot(Difficulty::Perfect));
+    sim.add_system(drive_paddle.in_set(PlayerInputSet))
+        .add_system(monitor.after(SimulationSet));
+    if let Some(generator) = endless {
+        endless::add_to_simulation(&mut sim, generator);
+    }
+    sim
+}
+
+// 用指定关卡模拟 minutes 分钟的游戏时间，全部检查通过并且至少通关一次时返回 true
+// 给出 endless 时为无尽模式，没有通关，改为要求至少结束一局
+pub fn run(minutes: f32, level: Level, coop: Coop, endless: Option<GeneratorConfig>) -> bool {
+    // 随机种子打印出来，发现问题时可以用同样的种子复现
+    let seed = rand::random();
+    let endless_mode = endless.is_some();
+    let mut sim = soak_simulation(level, coop, endless, seed);
+
+    println!(" 压力测试：模拟 {} 分钟，种子 {}", minutes, seed);
+    let frames = (minutes * 60.0 / STEP) as u64;
+    let report_every = (REPORT_INTERVAL / STEP) as u64;
+    for frame in 1..=frames {
+        sim.step();
+
+        let stats = sim.world.resource::<SoakStats>();
+        if stats.failure.is_some() {
+            break;
+        }
+        if frame % report_every == 0 {
+            println!(
+                " 已模拟 {} 分钟：{} 局，通关 {}，失败 {}，击碎砖块 {}",
+                frame / report_every * (REPORT_INTERVAL / 60.0) as u64,
+                stats.games, stats.wins, stats.losses, stats.bricks_broken,
+            );
+        }
+    }
+
+    let stats = sim.world.resource::<SoakStats>();
+    println!(
+        " 压力测试结束：{} 局，通关 {}，失败 {}，击碎砖块 {}",
+        stats.games, stats.wins, stats.losses, stats.bricks_broken,
+    );
+    match &stats.failure {
+        Some(reason) => {
+            println!(" 发现问题：{}", reason);
+            false
+        }
+        None if endless_mode && stats.games == 0 => {
+            println!(" 发现问题：一局都没有结束");
+            false
+        }
+        None if !endless_mode && stats.wins == 0 => {
+            println!(" 发现问题：一局都没有通关");
+            false
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coop::CoopLayout;
+
+    // 短时间的压力测试，保证 cargo test 也会跑到碰撞、胜负判定和无尽模式的下移
+    fn soak_seconds(sim: &mut Simulation, seconds: f32) -> &SoakStats {
+        for _ in 0..(seconds / STEP) as u32 {
+            sim.step();
+        }
+        let stats = sim.world.resource::<SoakStats>();
+        assert_eq!(stats.failure, None);
+        stats
+    }
+
+    #[test]
+    fn classic_level() {
+        let mut sim = soak_simulation(Level::default(), Coop::default(), None, 3);
+        assert!(soak_seconds(&mut sim, 60.0).bricks_broken > 0);
+    }
+
+    #[test]
+    fn coop_level() {
+        let mut sim = soak_simulation(Level::default(), Coop::two_players(CoopLayout::SideBySide), None, 5);
+        assert!(soak_seconds(&mut sim, 60.0).bricks_broken > 0);
+    }
+
+    #[test]
+    fn endless_mode() {
+        let generator = GeneratorConfig { seed: 7, rows: endless::START_ROWS, ..default() };
+        let level = crate::generator::generate(&generator).unwrap();
+        let mut sim = soak_simulation(level, Coop::default(), Some(generator), 7);
+        assert!(soak_seconds(&mut sim, 60.0).bricks_broken > 0);
+    }
+}
@@ -0,0 +1,218 @@
+// 观战服务器：把局面快照通过本地 TCP 连接发给外部工具（直播叠加层、数据面板、第二个观看窗口等）
+// 格式为每行一个 JSON 对象（换行分隔），字段见 Snapshot；坐标以场地中心为原点，y 轴向上，单位与游戏内相同
+// 每个观众有自己的发送线程和很短的队列，观众读得慢时丢弃旧快照，不会拖慢游戏
+use std::io::{self, BufWriter, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::scoring::Scoring;
+use crate::{Ball, Brick, GameState, Paddle, ARENA_HEIGHT, ARENA_WIDTH, BALL_SIZE, PADDLE_HEIGHT};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+pub const FORMAT_VERSION: u32 = 2;
+const SEND_INTERVAL: f32 = 1.0 / 30.0;  // 游戏中每秒最多发送 30 个快照
+const QUEUE_LENGTH: usize = 4;          // 每个观众最多积压的快照数
+
+// 一帧的局面，字段增减时提高 FORMAT_VERSION
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub frame: u64,           // 发送方的帧号，游戏中为已发送的快照序号，训练环境中为步数
+    pub time: f32,            // 本关已用时间（秒）
+    pub arena: [f32; 2],      // 场地宽高
+    pub score: u32,
+    pub lives: u32,
+    pub combo: u32,
+    pub game_over: bool,
+    pub won: bool,
+    pub paddles: Vec<PaddleSnapshot>,
+    pub balls: Vec<BallSnapshot>,
+    pub bricks: Vec<BrickSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaddleSnapshot {
+    pub player: usize,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BallSnapshot {
+    pub id: u32,
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub size: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BrickSnapshot {
+    pub row: u32,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub hp: u32,
+    pub max_hp: u32,
+    pub points: u32,
+    pub indestructible: bool,
+}
+
+impl Snapshot {
+    // 从游戏或模拟的 World 中读取局面，实体按固定顺序排列（挡板按玩家、球按编号、砖块按行和 x 坐标）
+    pub(crate) fn capture(world: &mut World, frame: u64) -> Self {
+        let mut paddles: Vec<PaddleSnapshot> = world
+            .query::<(&Transform, &Paddle)>()
+            .iter(world)
+            .map(|(transform, paddle)| PaddleSnapshot {
+                player: paddle.player,
+                x: transform.translation.x,
+                y: transform.translation.y,
+                width: paddle.width,
+                height: PADDLE_HEIGHT,
+            })
+            .collect();
+        paddles.sort_by_key(|paddle| paddle.player);
+        let mut balls: Vec<BallSnapshot> = world
+            .query::<(&Transform, &Ball)>()
+            .iter(world)
+            .map(|(transform, ball)| BallSnapshot {
+                id: ball.id,
+                x: transform.translation.x,
+                y: transform.translation.y,
+                vx: ball.velocity.x,
+                vy: ball.velocity.y,
+                size: BALL_SIZE,
+            })
+            .collect();
+        balls.sort_by_key(|ball| ball.id);
+        let mut bricks: Vec<BrickSnapshot> = world
+            .query::<(&Transform, &Brick)>()
+            .iter(world)
+            .filter(|(_, brick)| brick.hp > 0)
+            .map(|(transform, brick)| BrickSnapshot {
+                row: brick.row,
+                x: transform.translation.x,
+                y: transform.translation.y,
+                width: brick.size.x,
+                height: brick.size.y,
+                hp: brick.hp,
+                max_hp: brick.max_hp,
+                points: brick.points,
+                indestructible: brick.indestructible,
+            })
+            .collect();
+        bricks.sort_by(|a, b| a.row.cmp(&b.row).then(a.x.total_cmp(&b.x)));
+
+        let state = world.resource::<GameState>();
+        let scoring = world.resource::<Scoring>();
+        Self {
+            version: FORMAT_VERSION,
+            frame,
+            time: scoring.level_time,
+            arena: [ARENA_WIDTH, ARENA_HEIGHT],
+            score: state.score,
+            lives: state.lives,
+            combo: scoring.combo(),
+            game_over: state.game_over,
+            won: state.won,
+            paddles,
+            balls,
+            bricks,
+        }
+    }
+}
+
+type Spectators = Arc<Mutex<Vec<SyncSender<Arc<String>>>>>;
+
+// 接受观众连接并广播快照；可以放进游戏（见 SpectatorPlugin），也可以配合训练环境单独使用
+#[derive(Resource)]
+pub struct SpectatorServer {
+    address: SocketAddr,
+    spectators: Spectators,
+}
+
+impl SpectatorServer {
+    // 端口为 0 时由系统分配，用 address() 查看实际地址
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let spectators = Spectators::default();
+        let accepted = spectators.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (sender, receiver) = sync_channel(QUEUE_LENGTH);
+                accepted.lock().unwrap().push(sender);
+                thread::spawn(move || stream_snapshots(stream, receiver));
+            }
+        });
+        Ok(Self { address, spectators })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn spectators(&self) -> usize {
+        self.spectators.lock().unwrap().len()
+    }
+
+    // 发给所有观众，队列满的观众跳过这一帧，已断开的观众移除
+    pub fn broadcast(&self, snapshot: &Snapshot) {
+        let mut spectators = self.spectators.lock().unwrap();
+        if spectators.is_empty() {
+            return;
+        }
+        let line = Arc::new(serde_json::to_string(snapshot).expect("快照总能序列化") + "\n");
+        spectators.retain(|sender| !matches!(sender.try_send(line.clone()), Err(TrySendError::Disconnected(_))));
+    }
+}
+
+fn stream_snapshots(stream: TcpStream, receiver: Receiver<Arc<String>>) {
+    let peer = stream.peer_addr().map_or_else(|_| "?".to_string(), |address| address.to_string());
+    info!("观众 {} 已连接", peer);
+    let _ = stream.set_nodelay(true);
+    let mut writer = BufWriter::new(stream);
+    for line in receiver {
+        if writer.write_all(line.as_bytes()).and_then(|_| writer.flush()).is_err() {
+            break;
+        }
+    }
+    info!("观众 {} 已断开", peer);
+}
+
+pub struct SpectatorPlugin {
+    pub address: String,
+}
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        match SpectatorServer::bind(&self.address) {
+            Ok(server) => {
+                println!(" 观战服务器：{}", server.address());
+                app.insert_resource(server).add_system(broadcast_snapshot.in_base_set(CoreSet::PostUpdate));
+            }
+            Err(err) => eprintln!(" 观战服务器无法监听 {}：{}", self.address, err),
+        }
+    }
+}
+
+fn broadcast_snapshot(world: &mut World, mut frame: Local<u64>, mut since_sent: Local<f32>) {
+    *since_sent += world.resource::<Time>().delta_seconds();
+    if *since_sent < SEND_INTERVAL || world.resource::<SpectatorServer>().spectators() == 0 {
+        return;
+    }
+    *since_sent = 0.0;
+    *frame += 1;
+    let snapshot = Snapshot::capture(world, *frame);
+    world.resource::<SpectatorServer>().broadcast(&snapshot);
+}
@@ -0,0 +1,389 @@
+// 主题：游戏中用到的所有颜色、装饰尺寸和字体都从这里取，支持色盲配色和高对比度模式
+// 主题定义文件放在 assets/themes 下，内置主题编译时嵌入，目录中其他 .ron 文件启动时加载
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+const THEME_DIR: &str = "assets/themes";
+
+// 内置主题：(标识, 文件内容)
+const BUILTIN_THEMES: [(&str, &str); 3] = [
+    ("classic", include_str!("../assets/themes/classic.ron")),
+    ("neon", include_str!("../assets/themes/neon.ron")),
+    ("minimal", include_str!("../assets/themes/minimal.ron")),
+];
+
+// 整套配色，文件中颜色写成 "RRGGBB" 或 "RRGGBBAA"
+#[derive(Clone, Deserialize)]
+pub struct Palette {
+    #[serde(deserialize_with = "hex_color")]
+    pub background: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub paddle: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub ball: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub ball_fast: Color,           // 高速时球拖尾的颜色
+    #[serde(deserialize_with = "hex_colors")]
+    pub bricks: Vec<Color>,         // 按行循环使用
+    #[serde(deserialize_with = "hex_color")]
+    pub steel: Color,               // 不可击碎的砖块
+    #[serde(deserialize_with = "hex_colors")]
+    pub player_tints: Vec<Color>,   // 双人和对战时混入挡板与文字的玩家颜色，按玩家循环使用
+    #[serde(deserialize_with = "hex_color")]
+    pub garbage: Color,             // 对战中送给对手的垃圾砖
+    #[serde(deserialize_with = "hex_color")]
+    pub brick_border: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub brick_label: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub crack: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub outline: Color,             // 描边模式下的砖块边框
+    #[serde(deserialize_with = "hex_color")]
+    pub text_primary: Color,        // 分数、标题
+    #[serde(deserialize_with = "hex_color")]
+    pub text_info: Color,           // 说明和提示
+    #[serde(deserialize_with = "hex_color")]
+    pub text_normal: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub text_good: Color,           // 进行中、胜利、选中项
+    #[serde(deserialize_with = "hex_color")]
+    pub text_bad: Color,            // 失败、生命告急
+    #[serde(deserialize_with = "hex_color")]
+    pub text_warn: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub text_lives: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub panel: Color,               // 设置面板背景
+    #[serde(deserialize_with = "hex_color")]
+    pub backdrop: Color,            // 结束界面的半透明遮罩
+}
+
+fn parse_hex<E: serde::de::Error>(value: &str) -> Result<Color, E> {
+    Color::hex(value.trim_start_matches('#')).map_err(|_| E::custom(format!("颜色格式错误: {}", value)))
+}
+
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    parse_hex(&String::deserialize(deserializer)?)
+}
+
+fn hex_colors<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Color>, D::Error> {
+    let colors = Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|value| parse_hex(value))
+        .collect::<Result<Vec<_>, _>>()?;
+    if colors.is_empty() {
+        return Err(D::Error::custom("颜色列表至少需要一种颜色"));
+    }
+    Ok(colors)
+}
+
+// 装饰性精灵的尺寸，碰撞用的挡板、球和砖块尺寸不受主题影响
+#[derive(Clone, Deserialize)]
+pub struct Sizes {
+    pub paddle_glow: f32,     // 挡板发光比挡板大出的像素
+    pub ball_glow: f32,
+    pub brick_border: f32,
+    pub outline_border: f32,  // 描边模式下的边框
+}
+
+// 背景动画参数
+#[derive(Clone, Deserialize)]
+pub struct BackgroundStyle {
+    pub speed: f32,
+    pub variation: f32,            // 颜色变化幅度，0 为静止
+    pub texture: Option<String>,   // 可选的背景图片（相对 assets 目录），颜色作为染色
+}
+
+#[derive(Clone, Deserialize)]
+pub struct FontStyle {
+    pub path: Option<String>,  // 不填时使用默认界面字体
+    pub hud: f32,
+    pub help: f32,
+    pub headline: f32,         // 胜利/失败大字
+    pub modal: f32,
+    pub brick_label: f32,
+    pub popup: f32,            // 击碎砖块时的分数弹框
+    pub settings_title: f32,
+    pub settings_item: f32,    // 设置面板的提示行使用 help
+}
+
+// 贴图集中的一帧：(x, y, 宽, 高)，单位为像素
+pub type Frame = (f32, f32, f32, f32);
+
+pub fn frame_rect(frame: Frame) -> Rect {
+    let (x, y, width, height) = frame;
+    Rect::new(x, y, x + width, y + height)
+}
+
+// 可选的贴图集，图片用灰度绘制，显示时用主题颜色染色
+#[derive(Clone, Deserialize)]
+pub struct AtlasDef {
+    pub image: String,          // 相对 assets 目录
+    pub brick_frames: Vec<Frame>,  // 按损伤程度从完好到快碎排列
+    pub ball_frames: Vec<Frame>,
+    pub ball_fps: f32,
+    pub paddle_left: Frame,
+    pub paddle_middle: Frame,
+    pub paddle_right: Frame,
+    pub paddle_cap: f32,        // 端盖的显示宽度，挡板变宽时只拉伸中段
+}
+
+// 一个主题定义文件的内容
+#[derive(Clone, Deserialize)]
+pub struct ThemeDef {
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,  // 没有对应翻译时显示的名称
+    pub palette: Palette,
+    pub sizes: Sizes,
+    pub background: BackgroundStyle,
+    pub fonts: FontStyle,
+    #[serde(default)]
+    pub atlas: Option<AtlasDef>,  // 不填或图片缺失时用纯色矩形
+}
+
+fn parse_theme(id: &str, source: &str) -> Result<ThemeDef, ron::error::SpannedError> {
+    let mut def: ThemeDef = ron::from_str(source)?;
+    def.id = id.to_string();
+    Ok(def)
+}
+
+// 内置主题加上主题目录中的其他主题文件
+fn load_themes() -> Vec<ThemeDef> {
+    let mut themes: Vec<ThemeDef> = BUILTIN_THEMES
+        .iter()
+        .map(|(id, source)| {
+            parse_theme(id, source).unwrap_or_else(|err| panic!("主题文件 {}.ron 格式错误: {}", id, err))
+        })
+        .collect();
+
+    let Ok(entries) = std::fs::read_dir(THEME_DIR) else {
+        return themes;
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if themes.iter().any(|theme| theme.id == id) {
+            continue;
+        }
+        match read_theme(&path, id) {
+            Ok(def) => themes.push(def),
+            Err(err) => warn!("跳过主题文件 {}: {}", path.display(), err),
+        }
+    }
+    themes
+}
+
+fn read_theme(path: &Path, id: &str) -> Result<ThemeDef, String> {
+    let source = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    parse_theme(id, &source).map_err(|err| err.to_string())
+}
+
+// 色觉辅助模式，覆盖主题中容易混淆的颜色
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorMode {
+    Standard,
+    Deuteranopia,  // 绿色弱
+    Protanopia,    // 红色弱
+    Tritanopia,    // 蓝黄色弱
+    HighContrast,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 5] = [
+        ColorMode::Standard,
+        ColorMode::Deuteranopia,
+        ColorMode::Protanopia,
+        ColorMode::Tritanopia,
+        ColorMode::HighContrast,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            ColorMode::Standard => "color_mode.standard",
+            ColorMode::Deuteranopia => "color_mode.deuteranopia",
+            ColorMode::Protanopia => "color_mode.protanopia",
+            ColorMode::Tritanopia => "color_mode.tritanopia",
+            ColorMode::HighContrast => "color_mode.high_contrast",
+        }
+    }
+
+    // 红绿色弱使用 Okabe-Ito 配色，蓝黄色弱避开蓝/黄对比，各行亮度也拉开
+    fn apply(self, palette: &mut Palette) {
+        match self {
+            ColorMode::Standard => {}
+            ColorMode::Deuteranopia | ColorMode::Protanopia => {
+                palette.bricks = vec![
+                    Color::rgb(0.0, 0.45, 0.70),  // 蓝
+                    Color::rgb(0.34, 0.71, 0.91), // 天蓝
+                    Color::rgb(0.94, 0.89, 0.26), // 黄
+                    Color::rgb(0.90, 0.62, 0.0),  // 橙
+                    Color::rgb(0.80, 0.47, 0.65), // 紫红
+                ];
+                if self == ColorMode::Protanopia {
+                    // 红色弱者看红色偏暗，把紫红换成更亮的
+                    palette.bricks[4] = Color::rgb(0.95, 0.75, 0.85);
+                }
+                palette.text_good = Color::rgb(0.34, 0.71, 0.91);
+                palette.text_bad = Color::rgb(0.90, 0.62, 0.0);
+                palette.text_warn = Color::rgb(0.94, 0.89, 0.26);
+                palette.text_lives = Color::rgb(0.90, 0.62, 0.0);
+                palette.ball_fast = Color::rgb(0.34, 0.71, 0.91);
+                palette.player_tints = vec![Color::rgb(0.34, 0.71, 0.91), Color::rgb(0.90, 0.62, 0.0)];
+                palette.garbage = Color::rgb(0.6, 0.6, 0.6);
+            }
+            ColorMode::Tritanopia => {
+                palette.bricks = vec![
+                    Color::rgb(0.86, 0.15, 0.15), // 红
+                    Color::rgb(0.98, 0.55, 0.64), // 粉
+                    Color::rgb(0.95, 0.95, 0.95), // 白
+                    Color::rgb(0.0, 0.6, 0.6),    // 青
+                    Color::rgb(0.6, 0.85, 0.85),  // 浅青
+                ];
+                palette.ball = Color::rgb(0.98, 0.55, 0.64);
+                palette.ball_fast = Color::rgb(0.86, 0.15, 0.15);
+                palette.paddle = Color::rgb(0.0, 0.7, 0.7);
+                palette.text_primary = Color::rgb(0.98, 0.55, 0.64);
+                palette.text_good = Color::rgb(0.0, 0.75, 0.75);
+                palette.text_warn = Color::rgb(0.98, 0.55, 0.64);
+                palette.player_tints = vec![Color::rgb(0.0, 0.75, 0.75), Color::rgb(0.86, 0.15, 0.15)];
+                palette.garbage = Color::rgb(0.6, 0.6, 0.6);
+            }
+            ColorMode::HighContrast => {
+                palette.background = Color::BLACK;
+                palette.paddle = Color::WHITE;
+                palette.ball = Color::rgb(1.0, 1.0, 0.0);
+                palette.ball_fast = Color::rgb(1.0, 1.0, 0.0);
+                palette.bricks = vec![
+                    Color::WHITE,
+                    Color::rgb(1.0, 1.0, 0.0),
+                    Color::rgb(0.0, 1.0, 1.0),
+                    Color::rgb(1.0, 0.0, 1.0),
+                    Color::rgb(0.0, 1.0, 0.0),
+                ];
+                palette.steel = Color::GRAY;
+                palette.player_tints = vec![Color::rgb(0.0, 1.0, 1.0), Color::rgb(1.0, 0.0, 1.0)];
+                palette.garbage = Color::rgb(1.0, 0.5, 0.0);
+                palette.brick_border = Color::WHITE;
+                palette.brick_label = Color::BLACK;
+                palette.crack = Color::BLACK;
+                palette.text_primary = Color::WHITE;
+                palette.text_info = Color::WHITE;
+                palette.text_normal = Color::WHITE;
+                palette.text_good = Color::rgb(0.0, 1.0, 0.0);
+                palette.text_bad = Color::rgb(1.0, 1.0, 0.0);
+                palette.text_warn = Color::rgb(1.0, 1.0, 0.0);
+                palette.text_lives = Color::WHITE;
+                palette.panel = Color::BLACK;
+                palette.backdrop = Color::rgba(0.0, 0.0, 0.0, 0.85);
+            }
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct Theme {
+    pub color_mode: ColorMode,
+    pub brick_glyphs: bool,  // 在砖块上显示分值，不再只靠颜色区分
+    pub outlines: bool,      // 描边模式：砖块使用醒目的实线边框
+    presets: Vec<ThemeDef>,
+    current: usize,
+    colors: Palette,         // 应用色觉模式后的实际配色
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let presets = load_themes();
+        Self {
+            color_mode: ColorMode::Standard,
+            brick_glyphs: false,
+            outlines: false,
+            colors: presets[0].palette.clone(),
+            presets,
+            current: 0,
+        }
+    }
+}
+
+impl Theme {
+    pub fn def(&self) -> &ThemeDef {
+        &self.presets[self.current]
+    }
+
+    pub fn colors(&self) -> &Palette {
+        &self.colors
+    }
+
+    pub fn sizes(&self) -> &Sizes {
+        &self.def().sizes
+    }
+
+    pub fn fonts(&self) -> &FontStyle {
+        &self.def().fonts
+    }
+
+    // 切换到下一个主题，保留色觉模式等辅助设置
+    pub fn next_preset(&mut self) {
+        self.current = (self.current + 1) % self.presets.len();
+        self.refresh();
+    }
+
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.color_mode = mode;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.colors = self.def().palette.clone();
+        self.color_mode.apply(&mut self.colors);
+    }
+
+    // 高对比度模式下背景保持静止
+    pub fn background_variation(&self) -> f32 {
+        if self.color_mode == ColorMode::HighContrast {
+            0.0
+        } else {
+            self.def().background.variation
+        }
+    }
+
+    pub fn brick_color(&self, row: u32) -> Color {
+        let bricks = &self.colors.bricks;
+        bricks[row as usize % bricks.len()]
+    }
+
+    // 砖块边框的颜色和额外尺寸
+    pub fn brick_border(&self) -> (Color, f32) {
+        if self.outlines {
+            (self.colors.outline, self.sizes().outline_border)
+        } else {
+            (self.colors.brick_border, self.sizes().brick_border)
+        }
+    }
+}
+
+pub fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let [r1, g1, b1, a1] = from.as_rgba_f32();
+    let [r2, g2, b2, a2] = to.as_rgba_f32();
+    Color::rgba(
+        r1 + (r2 - r1) * t,
+        g1 + (g2 - g1) * t,
+        b1 + (b2 - b1) * t,
+        a1 + (a2 - a1) * t,
+    )
+}
@@ -0,0 +1,525 @@
+// 分屏对战：两位玩家各有一块场地和挡板，左右并排，用同一个种子开局
+// 每块场地是一个独立的确定性模拟（见 sim.rs），各有自己的 GameState、计分和随机数，窗口只负责输入和绘制
+// 每击碎 GARBAGE_EVERY 块砖向对手发送一次垃圾：在对手砖墙下方加一行砖，或者加快对手的球速
+// 先清空自己场地的一方获胜；有一方生命耗尽时另一方获胜
+// 场地的绘制和文字由 Arena 提供，联机对战和联机合作（见 net.rs）也用同一套画面
+use bevy::ecs::event::Events;
+use bevy::ecs::system::CommandQueue;
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::coop::player_tint;
+use crate::fonts::FontPlugin;
+use crate::level::{BrickDef, Level, MAX_LEVEL_ROWS};
+use crate::locale::{Locale, LocalePlugin};
+use crate::net::RollbackGame;
+use crate::sim::{SimState, Simulation, STEP};
+use crate::theme::Theme;
+use crate::{
+    brick_cell_position, spawn_brick, Ball, Brick, GameConfig, GameState, ImpactEvent, ImpactKind, Paddle,
+    PlayerInput, ARENA_HEIGHT, ARENA_WIDTH, BALL_SIZE, BRICK_COLS, PADDLE_HEIGHT,
+};
+
+pub(crate) const PLAYERS: usize = 2;
+const FIELD_GAP: f32 = 40.0;          // 两块场地之间的间隔
+const GARBAGE_EVERY: u32 = 3;         // 每击碎几块砖发送一次垃圾
+const GARBAGE: BrickDef = BrickDef { points: 5, hp: 1, indestructible: false };  // 垃圾砖一击即碎
+const SPEED_UP: f32 = 1.1;            // 每次加速的倍数
+const MAX_SPEED_UP: f32 = 1.6;        // 相对初始球速的上限
+const MAX_FRAME_TIME: f32 = 0.25;     // 卡顿时最多补这么多时间，避免一次模拟太多帧
+const VIEW_WIDTH: f32 = ARENA_WIDTH * 2.0 + FIELD_GAP;
+const VIEW_HEIGHT: f32 = ARENA_HEIGHT + 60.0;  // 场地上方留出分数栏
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Garbage {
+    Bricks,  // 对手砖墙下方多一行砖
+    Speed,   // 对手的球加速
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Outcome {
+    Winner(usize),
+    Draw,
+}
+
+struct Field {
+    sim: Simulation,
+    broken: u32,         // 本局击碎的砖块数
+    garbage_sent: u32,
+}
+
+impl Field {
+    fn new(config: &GameConfig, level: &Level, seed: u64) -> Self {
+        Self {
+            sim: Simulation::new(config.clone(), level.clone(), seed),
+            broken: 0,
+            garbage_sent: 0,
+        }
+    }
+
+    fn state(&self) -> &GameState {
+        self.sim.world.resource::<GameState>()
+    }
+
+    // 推进一帧，返回这一帧击碎的砖块数
+    fn step(&mut self, direction: f32) -> u32 {
+        let mut input = PlayerInput::default();
+        input.direction[0] = direction;
+        self.sim.world.insert_resource(input);
+        self.sim.step();
+
+        // 事件缓冲在每帧开始时交换，当前缓冲里正好是这一帧的事件
+        let events = self.sim.world.resource::<Events<ImpactEvent>>();
+        let broken = events
+            .iter_current_update_events()
+            .filter(|impact| impact.kind == ImpactKind::BrickBreak)
+            .count() as u32;
+        self.broken += broken;
+        broken
+    }
+
+    // 在砖墙最下面一行的下方加一行随机缺口的垃圾砖，已经到底时返回 false
+    fn add_garbage_row(&mut self, rng: &mut StdRng) -> bool {
+        let world = &mut self.sim.world;
+        let lowest = world.query::<&Brick>().iter(world).map(|brick| brick.row).max();
+        let row = lowest.map_or(0, |row| row + 1);
+        if row as usize >= MAX_LEVEL_ROWS {
+            return false;
+        }
+
+        let config = world.resource::<GameConfig>();
+        let size = Vec2::new(config.brick_width, config.brick_height);
+        let positions: Vec<Vec2> = (0..BRICK_COLS).map(|col| brick_cell_position(config, row as usize, col)).collect();
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+        let theme = world.resource::<Theme>();
+        // 至少留一个缺口，球总能穿过去；颜色记在模拟里的精灵上，回滚恢复后保持不变
+        let gap = rng.gen_range(0..BRICK_COLS);
+        for col in (0..BRICK_COLS).filter(|&col| col != gap && rng.gen_bool(0.7)) {
+            let brick = spawn_brick(&mut commands, theme, positions[col], size, GARBAGE, row);
+            commands.entity(brick).insert(Sprite {
+                color: theme.colors().garbage,
+                custom_size: Some(size),
+                ..default()
+            });
+        }
+        queue.apply(world);
+        true
+    }
+
+    // 球速提高 SPEED_UP 倍，已经达到上限时返回 false
+    fn speed_up(&mut self, base_speed: f32) -> bool {
+        let world = &mut self.sim.world;
+        let mut config = world.resource_mut::<GameConfig>();
+        let speed = (config.ball_speed * SPEED_UP).min(base_speed * MAX_SPEED_UP);
+        if speed <= config.ball_speed {
+            return false;
+        }
+        let ratio = speed / config.ball_speed;
+        config.ball_speed = speed;
+        for mut ball in world.query::<&mut Ball>().iter_mut(world) {
+            ball.velocity *= ratio;
+        }
+        true
+    }
+}
+
+// 对战的全部状态；场地的 World 和调度都可以跨线程移动，作为普通资源存放
+#[derive(Resource)]
+pub(crate) struct Versus {
+    fields: Vec<Field>,
+    config: GameConfig,
+    level: Level,
+    seed: u64,
+    rng: StdRng,            // 垃圾砖的缺口位置，也由种子决定
+    accumulator: f32,       // 还没有模拟的时间
+    outcome: Option<Outcome>,
+}
+
+impl Versus {
+    pub(crate) fn new(config: GameConfig, level: Level, seed: u64) -> Self {
+        Self {
+            fields: (0..PLAYERS).map(|_| Field::new(&config, &level, seed)).collect(),
+            config,
+            level,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            accumulator: 0.0,
+            outcome: None,
+        }
+    }
+
+    // 再来一局，双方换用同一个新种子
+    fn rematch(&mut self) {
+        *self = Self::new(self.config.clone(), self.level.clone(), self.seed.wrapping_add(1));
+    }
+
+    fn step(&mut self, directions: [f32; PLAYERS]) {
+        for (player, &direction) in directions.iter().enumerate() {
+            let broken = self.fields[player].step(direction);
+            let field = &mut self.fields[player];
+            let sends = (field.broken / GARBAGE_EVERY).saturating_sub(field.garbage_sent);
+            if broken > 0 && sends > 0 {
+                field.garbage_sent += sends;
+                for _ in 0..sends {
+                    self.send_garbage(1 - player);
+                }
+            }
+        }
+        self.outcome = self.judge();
+    }
+
+    // 垃圾砖和加速轮流发送，一种用完上限时改发另一种
+    fn send_garbage(&mut self, target: usize) {
+        let base_speed = self.config.ball_speed;
+        let order = if self.fields[1 - target].garbage_sent % 2 == 1 {
+            [Garbage::Bricks, Garbage::Speed]
+        } else {
+            [Garbage::Speed, Garbage::Bricks]
+        };
+        let field = &mut self.fields[target];
+        let sent = order.into_iter().any(|garbage| match garbage {
+            Garbage::Bricks => field.add_garbage_row(&mut self.rng),
+            Garbage::Speed => field.speed_up(base_speed),
+        });
+        if sent {
+            info!("玩家 {} 收到垃圾", target + 1);
+        }
+    }
+
+    // 通关的一方获胜；没有人通关时先耗尽生命的一方落败；同时结束时比较分数
+    fn judge(&self) -> Option<Outcome> {
+        let states: Vec<&GameState> = self.fields.iter().map(Field::state).collect();
+        if !states.iter().any(|state| state.game_over) {
+            return None;
+        }
+        let rank = |state: &GameState| (state.won, state.game_over && !state.won, state.score);
+        let [first, second] = [rank(states[0]), rank(states[1])];
+        let first_ahead = match (first.0, second.0, first.1, second.1) {
+            (true, false, _, _) => Some(true),
+            (false, true, _, _) => Some(false),
+            (false, false, true, false) => Some(false),
+            (false, false, false, true) => Some(true),
+            _ => (first.2 != second.2).then_some(first.2 > second.2),
+        };
+        Some(match first_ahead {
+            Some(true) => Outcome::Winner(0),
+            Some(false) => Outcome::Winner(1),
+            None => Outcome::Draw,
+        })
+    }
+}
+
+// 回滚用的快照：两块场地和垃圾的发送情况
+#[derive(Clone)]
+pub(crate) struct VersusState {
+    fields: Vec<(SimState, u32, u32)>,
+    rng: StdRng,
+    outcome: Option<Outcome>,
+}
+
+impl RollbackGame for Versus {
+    type State = VersusState;
+
+    fn save(&mut self) -> VersusState {
+        VersusState {
+            fields: self.fields.iter_mut().map(|field| (field.sim.save(), field.broken, field.garbage_sent)).collect(),
+            rng: self.rng.clone(),
+            outcome: self.outcome,
+        }
+    }
+
+    fn load(&mut self, state: &VersusState) {
+        for (field, (sim, broken, garbage_sent)) in self.fields.iter_mut().zip(&state.fields) {
+            field.sim.load(sim);
+            field.broken = *broken;
+            field.garbage_sent = *garbage_sent;
+        }
+        self.rng = state.rng.clone();
+        self.outcome = state.outcome;
+    }
+
+    fn advance(&mut self, directions: [f32; PLAYERS]) {
+        if self.outcome.is_none() {
+            self.step(directions);
+        }
+    }
+
+    fn checksum(&mut self) -> u64 {
+        let mut checksum = self.rng.clone().gen::<u64>() ^ self.outcome.is_some() as u64;
+        for field in &mut self.fields {
+            checksum = checksum.rotate_left(17) ^ field.sim.checksum();
+        }
+        checksum
+    }
+}
+
+// 画面上的一组场地：本地对战、联机对战和联机合作都实现它，共用下面的绘制和文字系统
+pub(crate) trait Arena {
+    fn fields(&self) -> Vec<&World>;
+    // 每块场地上方的分数栏
+    fn hud(&self, field: usize, locale: &Locale) -> String;
+    // 一局结束时的结果
+    fn outcome(&self, locale: &Locale) -> Option<String>;
+
+    // 画面中间的大字，没有时为空
+    fn banner(&self, locale: &Locale) -> String {
+        self.outcome(locale)
+            .map(|outcome| format!("{}\n{}", outcome, locale.get("versus.rematch")))
+            .unwrap_or_default()
+    }
+}
+
+impl Arena for Versus {
+    fn fields(&self) -> Vec<&World> {
+        self.fields.iter().map(|field| &field.sim.world).collect()
+    }
+
+    fn hud(&self, field: usize, locale: &Locale) -> String {
+        let world = &self.fields[field].sim.world;
+        let state = world.resource::<GameState>();
+        let speed = world.resource::<GameConfig>().ball_speed / self.config.ball_speed;
+        locale.format("versus.hud", &[
+            ("player", &(field + 1)),
+            ("score", &state.score),
+            ("lives", &state.lives),
+            ("speed", &format!("{:.1}", speed)),
+        ])
+    }
+
+    fn outcome(&self, locale: &Locale) -> Option<String> {
+        self.outcome.map(|outcome| match outcome {
+            Outcome::Winner(player) => locale.format("versus.winner", &[("player", &(player + 1))]),
+            Outcome::Draw => locale.get("versus.draw").to_string(),
+        })
+    }
+}
+
+// 场地在窗口中的水平偏移，只有一块场地时居中
+fn field_offset(field: usize, count: usize) -> f32 {
+    if count < 2 {
+        return 0.0;
+    }
+    let side = if field == 0 { -1.0 } else { 1.0 };
+    side * (ARENA_WIDTH + FIELD_GAP) / 2.0
+}
+
+// 镜像场地内容的精灵，每帧按模拟结果重新摆放，多余的隐藏
+#[derive(Component)]
+struct FieldSprite;
+
+#[derive(Component)]
+struct FieldText {
+    field: usize,
+}
+
+#[derive(Component)]
+struct OutcomeText;
+
+// 场地的绘制和文字，推进模拟的系统排在它前面
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct ArenaViewSet;
+
+pub fn run(level: Level, config: GameConfig) {
+    let seed = rand::random();
+    println!(" 对战模式，种子 {}", seed);
+    let mut app = arena_app(Versus::new(config, level, seed));
+    app.add_system(advance.before(ArenaViewSet)).run();
+}
+
+// 只有场地画面的窗口，A 是要显示的场地组
+pub(crate) fn arena_app<A: Arena + Resource>(arena: A) -> App {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: Locale::default().get("window.title").to_string(),
+                resolution: (VIEW_WIDTH * 0.75, VIEW_HEIGHT * 0.75).into(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .insert_resource(ClearColor(Color::BLACK))
+        .init_resource::<Theme>()
+        .insert_resource(arena)
+        .add_plugin(LocalePlugin)
+        .add_plugin(FontPlugin)
+        .add_startup_system(setup::<A>)
+        .add_systems((draw_fields::<A>, update_texts::<A>).in_set(ArenaViewSet));
+    app
+}
+
+fn setup<A: Arena + Resource>(mut commands: Commands, theme: Res<Theme>, arena: Res<A>) {
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: VIEW_WIDTH,
+        min_height: VIEW_HEIGHT,
+    };
+    commands.spawn(camera);
+
+    let count = arena.fields().len();
+    for field in 0..count {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: theme.colors().background,
+                custom_size: Some(Vec2::new(ARENA_WIDTH, ARENA_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_xyz(field_offset(field, count), -(VIEW_HEIGHT - ARENA_HEIGHT) / 2.0, -1.0),
+            ..default()
+        });
+        let left = if count < 2 { 27.0 } else { 2.0 + 50.0 * field as f32 };
+        commands.spawn((
+            TextBundle::default().with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(8.0),
+                    left: Val::Percent(left),
+                    ..default()
+                },
+                ..default()
+            }),
+            FieldText { field },
+        ));
+    }
+
+    commands.spawn(crate::centered_row(45.0)).with_children(|parent| {
+        parent.spawn((TextBundle::default().with_text_alignment(TextAlignment::Center), OutcomeText));
+    });
+}
+
+// 读取双方输入并按固定步长推进两块场地；玩家 1 用 A/D 或第一个手柄，玩家 2 用 ← → 或第二个手柄
+fn advance(
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut versus: ResMut<Versus>,
+    mut exit: EventWriter<bevy::app::AppExit>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        exit.send(bevy::app::AppExit);
+    }
+    let restart = keyboard.just_pressed(KeyCode::R)
+        || gamepads.iter().any(|gamepad| {
+            gamepad_buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::Start))
+        });
+    if versus.outcome.is_some() {
+        if restart {
+            versus.rematch();
+        }
+        return;
+    }
+
+    let mut directions = [0.0; PLAYERS];
+    for (player, (left, right)) in [(KeyCode::A, KeyCode::D), (KeyCode::Left, KeyCode::Right)].into_iter().enumerate() {
+        if keyboard.pressed(left) { directions[player] -= 1.0; }
+        if keyboard.pressed(right) { directions[player] += 1.0; }
+    }
+    for (player, gamepad) in gamepads.iter().enumerate().take(PLAYERS) {
+        let stick = gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0);
+        if stick.abs() > 0.2 { directions[player] += stick; }
+        if gamepad_buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadLeft)) { directions[player] -= 1.0; }
+        if gamepad_buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadRight)) { directions[player] += 1.0; }
+    }
+
+    versus.accumulator += time.delta_seconds().min(MAX_FRAME_TIME);
+    while versus.accumulator >= STEP && versus.outcome.is_none() {
+        versus.accumulator -= STEP;
+        versus.step(directions);
+    }
+}
+
+// 挡板按场地和玩家染色：对战时每块场地一位玩家，合作时一块场地两位玩家
+fn draw_fields<A: Arena + Resource>(
+    mut commands: Commands,
+    arena: Res<A>,
+    theme: Res<Theme>,
+    mut sprite_query: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<FieldSprite>>,
+) {
+    let colors = theme.colors();
+    let mut rects: Vec<(Vec3, Vec2, Color)> = Vec::new();
+    let fields = arena.fields();
+    for (index, world) in fields.iter().enumerate() {
+        let offset = Vec3::new(field_offset(index, fields.len()), -(VIEW_HEIGHT - ARENA_HEIGHT) / 2.0, 0.0);
+        for entity in world.iter_entities() {
+            let Some(transform) = entity.get::<Transform>() else { continue };
+            let position = transform.translation + offset;
+            if let Some(brick) = entity.get::<Brick>() {
+                let color = entity.get::<Sprite>().map_or(theme.brick_color(brick.row), |sprite| sprite.color);
+                // 受损的砖块变淡
+                let alpha = 0.4 + 0.6 * brick.hp as f32 / brick.max_hp.max(1) as f32;
+                rects.push((position, brick.size, color.with_a(alpha)));
+            } else if let Some(paddle) = entity.get::<Paddle>() {
+                let tint = player_tint(colors, index + paddle.player, colors.paddle);
+                rects.push((position, Vec2::new(paddle.width, PADDLE_HEIGHT), tint));
+            } else if entity.contains::<Ball>() {
+                rects.push((position + Vec3::Z, Vec2::splat(BALL_SIZE), colors.ball));
+            }
+        }
+    }
+
+    let mut rects = rects.into_iter();
+    for (mut transform, mut sprite, mut visibility) in sprite_query.iter_mut() {
+        match rects.next() {
+            Some((position, size, color)) => {
+                transform.translation = position;
+                sprite.custom_size = Some(size);
+                sprite.color = color;
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+    for (position, size, color) in rects {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            FieldSprite,
+        ));
+    }
+}
+
+fn update_texts<A: Arena + Resource>(
+    arena: Res<A>,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    mut field_query: Query<(&mut Text, &FieldText), Without<OutcomeText>>,
+    mut outcome_query: Query<&mut Text, With<OutcomeText>>,
+) {
+    let colors = theme.colors();
+    let fonts = theme.fonts();
+    let count = arena.fields().len();
+    for (mut text, field_text) in field_query.iter_mut() {
+        let value = arena.hud(field_text.field, &locale);
+        if text.sections.first().map(|section| &section.value) != Some(&value) {
+            let color = if count < 2 { colors.text_primary } else { player_tint(colors, field_text.field, colors.text_primary) };
+            *text = Text::from_section(value, TextStyle {
+                font: Default::default(),
+                font_size: fonts.hud,
+                color,
+            });
+        }
+    }
+
+    let value = arena.banner(&locale);
+    for mut text in outcome_query.iter_mut() {
+        if text.sections.first().map(|section| &section.value) != Some(&value) {
+            *text = Text::from_section(value.clone(), TextStyle {
+                font: Default::default(),
+                font_size: fonts.headline,
+                color: colors.text_good,
+            });
+        }
+    }
+}
@@ -0,0 +1,67 @@
+# Python 绑定测试，需要先安装模块：pip install . && pytest tests/python
+import numpy as np
+
+import brick_breaker
+
+
+def play(seed, steps=3000, **kwargs):
+    env = brick_breaker.Env(**kwargs)
+    observation = env.reset(seed)
+    total = 0.0
+    info = {}
+    for step in range(steps):
+        observation, reward, done, info = env.step(step * 7 % brick_breaker.ACTION_COUNT)
+        total += reward
+        if done:
+            break
+    return env, observation, total, info
+
+
+def test_seeded_episode_is_reproducible():
+    _, first, first_total, first_info = play(7, frame_size=(84, 84))
+    _, second, second_total, second_info = play(7, frame_size=(84, 84))
+
+    assert first["features"].shape == (brick_breaker.FEATURE_COUNT,)
+    assert first["features"].dtype == np.float32
+    assert first["frame"].shape == (84, 84)
+    assert first["frame"].dtype == np.uint8
+    np.testing.assert_array_equal(first["features"], second["features"])
+    np.testing.assert_array_equal(first["frame"], second["frame"])
+    assert first_total == second_total
+    assert first_info == second_info
+
+
+def test_replay_reproduces_episode():
+    env, observation, _, info = play(11, steps=200)
+    replay = brick_breaker.decode_replay(env.replay())
+    assert replay["seed"] == 11
+    assert len(replay["actions"]) == info["steps"]
+
+    again = brick_breaker.Env()
+    again.reset(replay["seed"])
+    for action in replay["actions"]:
+        replayed, _, _, _ = again.step(action)
+    np.testing.assert_array_equal(observation["features"], replayed["features"])
+
+
+def test_load_level():
+    level = brick_breaker.load_level("assets/levels/fortress.ron")
+    assert level.name == "堡垒"
+    assert max(hp for _, _, _, hp in level.bricks()) == 3
+
+    env = brick_breaker.Env(level=level)
+    env.reset(0)
+    _, _, _, info = env.step(0)
+    assert info["bricks_left"] == len(level.bricks())
+
+
+def test_vec_env():
+    envs = brick_breaker.VecEnv(4, frame_size=(42, 42))
+    observations = envs.reset(0)
+    assert observations["features"].shape == (4, brick_breaker.FEATURE_COUNT)
+    assert observations["frame"].shape == (4, 42, 42)
+
+    observations, rewards, dones, infos = envs.step([0, 1, 2, 1])
+    assert rewards.shape == (4,)
+    assert dones.shape == (4,)
+    assert len(infos) == 4
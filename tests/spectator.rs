@@ -0,0 +1,68 @@
+// 观战服务器测试：本地起一个服务器，由训练环境提供局面，客户端连接后应收到与环境一致的快照
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use brick_breaker::gym::{Action, BrickBreakerEnv, EnvConfig};
+use brick_breaker::spectate::{Snapshot, SpectatorServer, FORMAT_VERSION};
+
+#[test]
+fn client_receives_snapshots() {
+    let server = SpectatorServer::bind("127.0.0.1:0").expect("无法监听本地端口");
+    let stream = TcpStream::connect(server.address()).expect("无法连接服务器");
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let mut lines = BufReader::new(stream).lines();
+
+    // 服务器在后台线程中登记新观众，登记之前的快照不会发出
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while server.spectators() == 0 {
+        assert!(Instant::now() < deadline, "服务器没有登记观众");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let mut env = BrickBreakerEnv::new(EnvConfig::default());
+    env.reset(7);
+    let mut sent = Vec::new();
+    for step in 0..120 {
+        env.step(Action::from_index(step % Action::COUNT));
+        if step % 30 == 0 {
+            let snapshot = env.snapshot();
+            server.broadcast(&snapshot);
+            sent.push(snapshot);
+        }
+    }
+
+    for expected in &sent {
+        let line = lines.next().expect("连接提前关闭").expect("读取快照超时");
+        let received: Snapshot = serde_json::from_str(&line).expect("快照不是有效的 JSON");
+        assert_eq!(&received, expected);
+    }
+
+    let last = sent.last().unwrap();
+    assert_eq!(last.version, FORMAT_VERSION);
+    assert_eq!(last.frame, 91);
+    assert_eq!(last.paddles.len(), 1);
+    assert!(!last.balls.is_empty());
+    assert!(!last.bricks.is_empty());
+}
+
+#[test]
+fn disconnected_clients_are_dropped() {
+    let server = SpectatorServer::bind("127.0.0.1:0").unwrap();
+    drop(TcpStream::connect(server.address()).unwrap());
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while server.spectators() == 0 {
+        assert!(Instant::now() < deadline, "服务器没有登记观众");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    // 发送线程写入失败后退出，之后的广播会移除这个观众
+    let mut env = BrickBreakerEnv::new(EnvConfig::default());
+    env.reset(1);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while server.spectators() > 0 {
+        assert!(Instant::now() < deadline, "断开的观众没有被移除");
+        server.broadcast(&env.snapshot());
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}